@@ -0,0 +1,133 @@
+//! A data dictionary for the flattened response csvs, so whoever receives
+//! `result_massaged.csv` doesn't have to guess what each column means.
+
+use std::fmt::Write as _;
+
+use clap::ValueEnum;
+use csv::StringRecord;
+use serde::Serialize;
+
+use crate::{ITEMS, Imputation, METADATA_COLUMNS, display_name, markdown};
+
+/// What one column of the flattened responses holds.
+#[derive(Debug, Serialize)]
+pub struct Column {
+    pub name: String,
+    pub item: &'static str,
+    /// The question as it was worded in the survey export's header.
+    pub question: String,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    /// The values the column can take.
+    pub range: &'static str,
+    /// What an empty cell means.
+    pub missing: String,
+    /// What was done to the raw answer on the way in.
+    pub massage: String,
+}
+
+/// Describes every column of a [`FlattenedResponse`](crate::FlattenedResponse) csv, taking
+/// the question text from `headers` and noting `imputation` if missing answers were filled.
+pub fn columns(headers: &StringRecord, imputation: Option<Imputation>) -> Vec<Column> {
+    let filled = imputation
+        .and_then(|i| i.to_possible_value())
+        .and_then(|v| {
+            v.get_help()
+                .map(|help| format!(" Missing answers were filled with {help}."))
+        })
+        .unwrap_or_default();
+
+    ITEMS
+        .iter()
+        .enumerate()
+        .flat_map(|(i, item)| {
+            let question = |offset: usize| {
+                headers
+                    .get(METADATA_COLUMNS + i * 3 + offset)
+                    .unwrap_or_default()
+                    .to_owned()
+            };
+            let rancidness = |name: &str, offset: usize| Column {
+                name: format!("{item}_{name}_rancidness"),
+                item,
+                question: question(offset),
+                kind: "number",
+                range: "1 to 5, 1 being fresh and 5 absolutely rotten",
+                missing: format!(
+                    "The answer couldn't be read as a number: it was blank, prose, \
+                     unparseable or a refusal to throw the item at all. result_missing.csv \
+                     breaks these down by reason.{}",
+                    if imputation.is_some() {
+                        " Only left empty when imputation had nothing to go on."
+                    } else {
+                        ""
+                    }
+                ),
+                massage: format!(
+                    "The first number in the answer was taken, with any surrounding text kept \
+                     as a note. Answers mentioning \"fresh\" read as 1. Numbers outside the \
+                     scale were clamped to 1 or 5.{filled}"
+                ),
+            };
+            let reason = |name: &str, offset: usize| Column {
+                name: format!("{item}_{name}_missing"),
+                item,
+                question: question(offset),
+                kind: "text",
+                range: "blank, text, unparseable or refused",
+                missing: format!(
+                    "Empty when the answer was read as a number. When `{item}_{name}_rancidness` \
+                     is filled in anyway, it was imputed."
+                ),
+                massage: "Why the answer couldn't be read, see result_missing.csv.".to_owned(),
+            };
+
+            [
+                Column {
+                    name: format!("{item}_would_throw"),
+                    item,
+                    question: question(0),
+                    kind: "boolean",
+                    range: "true or false",
+                    missing: "Never empty. Rows without a Yes or No here are set aside in \
+                              result_rejects.csv."
+                        .to_owned(),
+                    massage: "Yes and No read as true and false.".to_owned(),
+                },
+                rancidness("expected", 1),
+                reason("expected", 1),
+                rancidness("desired", 2),
+                reason("desired", 2),
+            ]
+        })
+        .collect()
+}
+
+/// Renders the codebook as a markdown table per item.
+pub fn markdown(columns: &[Column]) -> String {
+    let mut text = "# Codebook\n\nThe columns of `result_massaged.csv`, one row per respondent, \
+                    with five columns per item.\n"
+        .to_owned();
+
+    for (item, columns) in ITEMS.iter().zip(columns.chunks(5)) {
+        let _ = write!(
+            text,
+            "\n## {}\n\n| Column | Question | Type | Range | Missing | Massage |\n|---|---|---|---|---|---|\n",
+            display_name(item)
+        );
+        for column in columns {
+            let _ = writeln!(
+                text,
+                "| `{}` | {} | {} | {} | {} | {} |",
+                column.name,
+                markdown::escape(&column.question),
+                column.kind,
+                column.range,
+                markdown::escape(&column.missing),
+                markdown::escape(&column.massage)
+            );
+        }
+    }
+
+    text
+}
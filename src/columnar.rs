@@ -0,0 +1,295 @@
+//! Writes the long-format answers and the per-item report as parquet and arrow ipc files,
+//! for loading straight into dataframe tools.
+
+use std::{fs::File, path::Path, sync::Arc};
+
+use arrow::{
+    array::{
+        ArrayRef, BooleanArray, FixedSizeListBuilder, Float64Array, Float64Builder, RecordBatch,
+        StringArray, TimestampNanosecondArray, UInt32Array, UInt64Array,
+    },
+    compute::kernels::cast_utils::string_to_timestamp_nanos,
+    datatypes::{DataType, Field, Schema},
+    ipc::writer::FileWriter,
+};
+use parquet::arrow::ArrowWriter;
+
+use crate::{Error, ITEMS, ItemReport, Survey, label};
+
+/// Writes each batch into `directory` as both `<name>.parquet` and `<name>.arrow`, creating
+/// the directory if needed.
+pub fn write(directory: &Path, batches: &[(&str, RecordBatch)]) -> Result<(), Error> {
+    std::fs::create_dir_all(directory)?;
+
+    for (name, batch) in batches {
+        let mut writer = ArrowWriter::try_new(
+            File::create(directory.join(name).with_extension("parquet"))?,
+            batch.schema(),
+            None,
+        )?;
+        writer.write(batch)?;
+        writer.close()?;
+
+        let mut writer = FileWriter::try_new(
+            File::create(directory.join(name).with_extension("arrow"))?,
+            &batch.schema(),
+        )?;
+        writer.write(batch)?;
+        writer.finish()?;
+    }
+
+    Ok(())
+}
+
+/// One row per respondent and item of a massaged survey, see [`Survey::massage`].
+pub fn answers(survey: &Survey, weights: &[f64]) -> Result<RecordBatch, Error> {
+    let rows = survey
+        .records
+        .iter()
+        .zip(&survey.responses)
+        .zip(weights)
+        .enumerate()
+        .flat_map(|(respondent, ((record, response), weight))| {
+            ITEMS
+                .into_iter()
+                .zip(response.fruits())
+                .map(move |(item, fruit)| (respondent, record, *weight, item, fruit))
+        })
+        .collect::<Vec<_>>();
+
+    let non_empty = |i: usize| {
+        rows.iter()
+            .map(|(_, record, ..)| record.get(i).filter(|c| !c.is_empty()))
+            .collect::<StringArray>()
+    };
+    let timestamp = rows
+        .iter()
+        .map(|(_, record, ..)| {
+            record
+                .get(2)
+                .and_then(|t| string_to_timestamp_nanos(t).ok())
+        })
+        .collect::<TimestampNanosecondArray>()
+        .with_timezone("UTC");
+
+    let columns: Vec<(&str, bool, ArrayRef)> = vec![
+        (
+            "respondent",
+            false,
+            Arc::new(
+                rows.iter()
+                    .map(|(respondent, ..)| *respondent as u32)
+                    .collect::<UInt32Array>(),
+            ),
+        ),
+        (
+            "line",
+            true,
+            Arc::new(
+                rows.iter()
+                    .map(|(_, record, ..)| record.position().map(|p| p.line()))
+                    .collect::<UInt64Array>(),
+            ),
+        ),
+        ("user_id", true, Arc::new(non_empty(0))),
+        ("display_name", true, Arc::new(non_empty(1))),
+        ("timestamp", true, Arc::new(timestamp)),
+        (
+            "weight",
+            false,
+            Arc::new(
+                rows.iter()
+                    .map(|(_, _, weight, ..)| *weight)
+                    .collect::<Float64Array>(),
+            ),
+        ),
+        (
+            "item",
+            false,
+            Arc::new(
+                rows.iter()
+                    .map(|(_, _, _, item, _)| Some(*item))
+                    .collect::<StringArray>(),
+            ),
+        ),
+        (
+            "would_throw",
+            false,
+            Arc::new(
+                rows.iter()
+                    .map(|(.., fruit)| Some(fruit.would_throw))
+                    .collect::<BooleanArray>(),
+            ),
+        ),
+        (
+            "expected_rancidness",
+            true,
+            Arc::new(
+                rows.iter()
+                    .map(|(.., fruit)| fruit.expected_rancidness)
+                    .collect::<Float64Array>(),
+            ),
+        ),
+        (
+            "desired_rancidness",
+            true,
+            Arc::new(
+                rows.iter()
+                    .map(|(.., fruit)| fruit.desired_rancidness)
+                    .collect::<Float64Array>(),
+            ),
+        ),
+        (
+            "expected_missing",
+            true,
+            Arc::new(
+                rows.iter()
+                    .map(|(.., fruit)| label(fruit.expected_missing))
+                    .collect::<StringArray>(),
+            ),
+        ),
+        (
+            "desired_missing",
+            true,
+            Arc::new(
+                rows.iter()
+                    .map(|(.., fruit)| label(fruit.desired_missing))
+                    .collect::<StringArray>(),
+            ),
+        ),
+        (
+            "imputation",
+            true,
+            Arc::new(
+                rows.iter()
+                    .map(|(.., fruit)| label(fruit.imputation))
+                    .collect::<StringArray>(),
+            ),
+        ),
+        (
+            "notes",
+            false,
+            Arc::new(
+                rows.iter()
+                    .map(|(.., fruit)| Some(fruit.notes.as_str()))
+                    .collect::<StringArray>(),
+            ),
+        ),
+    ];
+
+    batch(columns)
+}
+
+/// One row per item, with its weighted histograms as fixed size lists of five bins.
+pub fn report(reports: &[ItemReport]) -> Result<RecordBatch, Error> {
+    let floats = |f: fn(&ItemReport) -> f64| -> ArrayRef {
+        Arc::new(reports.iter().map(f).collect::<Float64Array>())
+    };
+    let counts = |f: fn(&ItemReport) -> usize| -> ArrayRef {
+        Arc::new(reports.iter().map(|r| f(r) as u64).collect::<UInt64Array>())
+    };
+    let histogram = |f: fn(&ItemReport) -> [f64; 5]| -> ArrayRef {
+        let mut builder = FixedSizeListBuilder::new(Float64Builder::new(), 5)
+            .with_field(Field::new("item", DataType::Float64, false));
+        for report in reports {
+            builder.values().append_slice(&f(report));
+            builder.append(true);
+        }
+        Arc::new(builder.finish())
+    };
+
+    batch(vec![
+        (
+            "item",
+            false,
+            Arc::new(
+                reports
+                    .iter()
+                    .map(|r| Some(r.item))
+                    .collect::<StringArray>(),
+            ),
+        ),
+        ("would_throw_count", false, counts(|r| r.would_throw_count)),
+        (
+            "would_not_throw_count",
+            false,
+            counts(|r| r.would_not_throw_count),
+        ),
+        (
+            "weighted_would_throw_count",
+            false,
+            floats(|r| r.weighted_would_throw_count),
+        ),
+        (
+            "weighted_would_not_throw_count",
+            false,
+            floats(|r| r.weighted_would_not_throw_count),
+        ),
+        ("refusal_count", false, counts(|r| r.refusal_count)),
+        (
+            "contradiction_count",
+            false,
+            counts(|r| r.contradiction_count),
+        ),
+        (
+            "weighted_refusal_count",
+            false,
+            floats(|r| r.weighted_refusal_count),
+        ),
+        (
+            "weighted_contradiction_count",
+            false,
+            floats(|r| r.weighted_contradiction_count),
+        ),
+        (
+            "average_expected_rancidness",
+            false,
+            floats(|r| r.average_expected_rancidness),
+        ),
+        (
+            "average_desired_rancidness",
+            false,
+            floats(|r| r.average_desired_rancidness),
+        ),
+        (
+            "median_expected_rancidness",
+            false,
+            floats(|r| r.median_expected_rancidness),
+        ),
+        (
+            "median_desired_rancidness",
+            false,
+            floats(|r| r.median_desired_rancidness),
+        ),
+        (
+            "effective_sample_size",
+            false,
+            floats(|r| r.effective_sample_size),
+        ),
+        (
+            "expected_histogram",
+            false,
+            histogram(|r| r.expected_histogram),
+        ),
+        (
+            "desired_histogram",
+            false,
+            histogram(|r| r.desired_histogram),
+        ),
+    ])
+}
+
+// builds the schema from the columns themselves, with each column's nullability spelled out
+fn batch(columns: Vec<(&str, bool, ArrayRef)>) -> Result<RecordBatch, Error> {
+    let schema = Schema::new(
+        columns
+            .iter()
+            .map(|(name, nullable, array)| Field::new(*name, array.data_type().clone(), *nullable))
+            .collect::<Vec<_>>(),
+    );
+
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        columns.into_iter().map(|(.., array)| array).collect(),
+    )?)
+}
@@ -0,0 +1,211 @@
+//! Hand corrections applied on top of the raw survey rows during ingest, so the export itself
+//! never has to be edited.
+
+use std::path::Path;
+
+use csv::{Reader, StringRecord};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{ITEMS, METADATA_COLUMNS};
+
+/// One of the three answers given about each item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Field {
+    WouldThrow,
+    ExpectedRancidness,
+    DesiredRancidness,
+}
+
+impl Field {
+    // where the field sits among its item's three columns
+    pub(crate) fn offset(self) -> usize {
+        match self {
+            Self::WouldThrow => 0,
+            Self::ExpectedRancidness => 1,
+            Self::DesiredRancidness => 2,
+        }
+    }
+}
+
+/// A replacement for one raw cell, keyed by the row it's on (counting data rows from 1), by
+/// the respondent's User ID, or by both, in which case the row has to hold that User ID.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Override {
+    pub row: Option<usize>,
+    pub user_id: Option<String>,
+    pub item: String,
+    pub field: Field,
+    /// The raw text to read in place of what the respondent wrote.
+    pub value: String,
+}
+
+impl Override {
+    fn user_id(&self) -> Option<&str> {
+        self.user_id.as_deref().filter(|id| !id.is_empty())
+    }
+
+    fn matches(&self, row: usize, record: &StringRecord) -> bool {
+        let row_matches = self.row.is_none_or(|r| r == row);
+        let user_matches = self
+            .user_id()
+            .is_none_or(|id| record.get(0).is_some_and(|c| c.trim() == id));
+        (self.row.is_some() || self.user_id().is_some()) && row_matches && user_matches
+    }
+
+    // the row the override is meant for, as a person would describe it
+    pub(crate) fn target(&self) -> String {
+        match (self.row, self.user_id()) {
+            (Some(row), Some(id)) => format!("row {row} (user {id})"),
+            (Some(row), None) => format!("row {row}"),
+            (None, id) => format!("user {}", id.unwrap_or_default()),
+        }
+    }
+}
+
+/// An override as it was applied, for the corrections log.
+#[derive(Debug, Clone, Serialize)]
+pub struct Correction {
+    pub row: usize,
+    /// The line the row started on.
+    pub line: u64,
+    pub user_id: String,
+    pub item: &'static str,
+    pub field: Field,
+    /// What the respondent actually wrote.
+    pub original: String,
+    pub corrected: String,
+}
+
+/// Reads overrides from a csv with `row`, `user_id`, `item`, `field` and `value` columns,
+/// each override filling in `row`, `user_id` or both.
+pub fn read(path: &Path) -> Result<Vec<Override>, String> {
+    let overrides = Reader::from_path(path)
+        .map_err(|e| format!("couldn't open corrections file: {e}"))?
+        .deserialize()
+        .collect::<Result<Vec<Override>, _>>()
+        .map_err(|e| format!("malformed corrections file: {e}"))?;
+
+    for o in &overrides {
+        if !ITEMS.contains(&o.item.as_str()) {
+            return Err(format!("unknown item in corrections file: {}", o.item));
+        }
+        if o.row.is_none() && o.user_id().is_none() {
+            return Err("correction without a row or user id".to_owned());
+        }
+    }
+
+    Ok(overrides)
+}
+
+// overrides being applied row by row, keeping track of which have found their row
+pub(crate) struct Overlay<'o> {
+    overrides: &'o [Override],
+    used: Vec<bool>,
+}
+
+impl<'o> Overlay<'o> {
+    pub(crate) fn new(overrides: &'o [Override]) -> Self {
+        Self {
+            overrides,
+            used: vec![false; overrides.len()],
+        }
+    }
+
+    // a copy of the `row`th record with every matching override patched in
+    pub(crate) fn apply(
+        &mut self,
+        row: usize,
+        record: &StringRecord,
+    ) -> (StringRecord, Vec<Correction>) {
+        let mut cells = record.iter().map(str::to_owned).collect::<Vec<_>>();
+        let mut corrections = Vec::new();
+
+        for (o, used) in self.overrides.iter().zip(&mut self.used) {
+            if !o.matches(row, record) {
+                continue;
+            }
+            let Some(item) = ITEMS.iter().position(|i| *i == o.item) else {
+                continue;
+            };
+            let column = METADATA_COLUMNS + item * 3 + o.field.offset();
+            if cells.len() <= column {
+                cells.resize(column + 1, String::new());
+            }
+
+            *used = true;
+            corrections.push(Correction {
+                row,
+                line: record.position().map_or(0, |p| p.line()),
+                user_id: record.get(0).unwrap_or_default().to_owned(),
+                item: ITEMS[item],
+                field: o.field,
+                original: std::mem::replace(&mut cells[column], o.value.clone()),
+                corrected: o.value.clone(),
+            });
+        }
+
+        let mut patched = StringRecord::from(cells);
+        patched.set_position(record.position().cloned());
+        (patched, corrections)
+    }
+
+    // an override that never found its row, if there is one
+    pub(crate) fn unused(&self) -> Option<&'o Override> {
+        self.overrides
+            .iter()
+            .zip(&self.used)
+            .find_map(|(o, used)| (!used).then_some(o))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with(row: Option<usize>, user_id: Option<&str>) -> Override {
+        Override {
+            row,
+            user_id: user_id.map(str::to_owned),
+            item: "kiwi".to_owned(),
+            field: Field::DesiredRancidness,
+            value: "3".to_owned(),
+        }
+    }
+
+    #[test]
+    fn matches_by_row_or_user_id() {
+        let record = StringRecord::from(vec!["u1", "Someone", "today"]);
+
+        assert!(with(Some(2), None).matches(2, &record));
+        assert!(!with(Some(3), None).matches(2, &record));
+        assert!(with(None, Some("u1")).matches(7, &record));
+        assert!(!with(None, Some("u2")).matches(7, &record));
+    }
+
+    #[test]
+    fn row_and_user_id_have_to_agree() {
+        let record = StringRecord::from(vec!["u1", "Someone", "today"]);
+
+        assert!(with(Some(2), Some("u1")).matches(2, &record));
+        assert!(!with(Some(2), Some("u2")).matches(2, &record));
+        assert!(!with(Some(3), Some("u1")).matches(2, &record));
+        assert_eq!(with(Some(2), Some("u2")).target(), "row 2 (user u2)");
+    }
+
+    #[test]
+    fn overlay_patches_the_matching_cell() {
+        let overrides = [with(None, Some("u1"))];
+        let mut overlay = Overlay::new(&overrides);
+        let record = StringRecord::from(vec!["u1", "Someone", "today"]);
+
+        let (patched, corrections) = overlay.apply(1, &record);
+        let column = METADATA_COLUMNS
+            + ITEMS.iter().position(|i| *i == "kiwi").unwrap() * 3
+            + Field::DesiredRancidness.offset();
+        assert_eq!(patched.get(column), Some("3"));
+        assert_eq!(corrections.len(), 1);
+        assert!(overlay.unused().is_none());
+    }
+}
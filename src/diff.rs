@@ -0,0 +1,527 @@
+//! What changed between two runs: per-item report numbers, respondents coming and going, and
+//! answers that were read differently.
+
+use std::{collections::BTreeMap, fmt::Write as _, fs::File, path::Path};
+
+use csv::{Reader, StringRecord};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Error, Fruit, ITEMS, ItemReport, METADATA_COLUMNS, Question, Response, Survey, VecResponse,
+    corrections::Field,
+    label,
+    missing::{self, Imputation},
+    schema::Artifact,
+    weights::Weighting,
+};
+
+/// The report numbers compared between runs, for one item.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReportRow {
+    pub item: String,
+    pub would_throw_count: f64,
+    pub would_not_throw_count: f64,
+    pub average_expected_rancidness: f64,
+    pub average_desired_rancidness: f64,
+}
+
+impl From<&ItemReport> for ReportRow {
+    fn from(report: &ItemReport) -> Self {
+        Self {
+            item: report.item.to_owned(),
+            would_throw_count: report.would_throw_count as f64,
+            would_not_throw_count: report.would_not_throw_count as f64,
+            average_expected_rancidness: report.average_expected_rancidness,
+            average_desired_rancidness: report.average_desired_rancidness,
+        }
+    }
+}
+
+// the report numbers compared, in the order `ReportRow::metrics` gives them
+const METRICS: [&str; 4] = [
+    "would_throw_count",
+    "would_not_throw_count",
+    "average_expected_rancidness",
+    "average_desired_rancidness",
+];
+
+impl ReportRow {
+    fn metrics(&self) -> [f64; 4] {
+        [
+            self.would_throw_count,
+            self.would_not_throw_count,
+            self.average_expected_rancidness,
+            self.average_desired_rancidness,
+        ]
+    }
+}
+
+/// One side of a diff: either a report the cruncher wrote, or a survey to run through the
+/// pipeline.
+#[derive(Debug)]
+pub enum Run {
+    Report(Vec<ReportRow>),
+    Survey(Survey),
+}
+
+impl Run {
+    /// Reads a run from `path`.
+    ///
+    /// `result_summary.json`, and csvs laid out like `result_summary.csv` (an `item` column)
+    /// or `result.csv` (a row of `<item>_<metric>` columns), are read as reports. Anything
+    /// else is ingested as a survey, see [`Survey::from_path`].
+    pub fn from_path(path: &Path) -> Result<Self, Error> {
+        if Artifact::from_path(path) == Some(Artifact::Report) {
+            return Ok(Self::Report(serde_json::from_reader(File::open(path)?)?));
+        }
+
+        let is_csv = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("csv"));
+        if is_csv {
+            let mut reader = Reader::from_path(path)?;
+            let headers = reader.headers()?.clone();
+            if headers.get(0) == Some("item") {
+                return Ok(Self::Report(
+                    reader.deserialize().collect::<Result<_, _>>()?,
+                ));
+            }
+            if headers.iter().any(|h| h == "artichoke_would_throw_count") {
+                let record = reader.records().next().transpose()?.unwrap_or_default();
+                return Ok(Self::Report(flattened(&headers, &record)));
+            }
+        }
+
+        Ok(Self::Survey(Survey::from_path(path)?))
+    }
+
+    // the report numbers, working them out the way `options` says for a survey
+    fn report(&self, options: &Options) -> Result<Vec<ReportRow>, Error> {
+        match self {
+            Self::Report(rows) => Ok(rows.clone()),
+            Self::Survey(survey) => {
+                let weights = survey.weights(&options.weighting)?;
+                let mut responses = survey
+                    .responses
+                    .iter()
+                    .cloned()
+                    .map(Response::massage)
+                    .collect::<Vec<_>>();
+                if let Some(imputation) = options.imputation {
+                    missing::impute(&mut responses, imputation);
+                }
+                Ok(responses
+                    .into_iter()
+                    .collect::<VecResponse>()
+                    .report(&weights)
+                    .iter()
+                    .map(ReportRow::from)
+                    .collect())
+            }
+        }
+    }
+}
+
+/// How survey runs are reported on before comparing, which has to match how any report
+/// they're compared against was crunched for the numbers to line up.
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub weighting: Weighting,
+    pub imputation: Option<Imputation>,
+}
+
+// reads the single row of a result.csv back into one row per item
+fn flattened(headers: &StringRecord, record: &StringRecord) -> Vec<ReportRow> {
+    let value = |item: &str, metric: &str| {
+        headers
+            .iter()
+            .position(|h| h == format!("{item}_{metric}"))
+            .and_then(|i| record.get(i))
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(f64::NAN)
+    };
+
+    ITEMS
+        .iter()
+        .map(|item| ReportRow {
+            item: (*item).to_owned(),
+            would_throw_count: value(item, "would_throw_count"),
+            would_not_throw_count: value(item, "would_not_throw_count"),
+            average_expected_rancidness: value(item, "average_expected_rancidness"),
+            average_desired_rancidness: value(item, "average_desired_rancidness"),
+        })
+        .collect()
+}
+
+/// A report number that moved. `old` or `new` is empty when the item is only in one run.
+#[derive(Debug, Serialize)]
+pub struct ItemChange {
+    pub item: String,
+    pub metric: &'static str,
+    pub old: Option<f64>,
+    pub new: Option<f64>,
+}
+
+/// A respondent only in one of the runs.
+#[derive(Debug, Serialize)]
+pub struct RespondentChange {
+    /// The User ID, the timestamp when either run has no User IDs, or else the row.
+    pub respondent: String,
+    pub added: bool,
+}
+
+/// An answer a respondent in both runs was read as differently.
+#[derive(Debug, Serialize)]
+pub struct CellChange {
+    pub respondent: String,
+    pub item: &'static str,
+    pub field: Field,
+    pub old: String,
+    pub new: String,
+    /// What the respondent wrote, as of each run. Runs read back from json only have the
+    /// value the response held.
+    pub old_raw: String,
+    pub new_raw: String,
+}
+
+/// Everything that changed between two runs.
+#[derive(Debug, Default, Serialize)]
+pub struct Diff {
+    pub items: Vec<ItemChange>,
+    /// Only filled in when both runs are surveys.
+    pub respondents: Vec<RespondentChange>,
+    /// Only filled in when both runs are surveys.
+    pub cells: Vec<CellChange>,
+}
+
+impl Diff {
+    /// Whether the runs came out the same.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty() && self.respondents.is_empty() && self.cells.is_empty()
+    }
+}
+
+/// Compares two runs, reporting on surveys as `options` says. Report numbers are compared to
+/// within a rounding error.
+pub fn diff(old: &Run, new: &Run, options: &Options) -> Result<Diff, Error> {
+    let mut diff = Diff {
+        items: items(&old.report(options)?, &new.report(options)?),
+        ..Diff::default()
+    };
+
+    if let (Run::Survey(old), Run::Survey(new)) = (old, new) {
+        respondents(old, new, &mut diff);
+    }
+
+    Ok(diff)
+}
+
+fn items(old: &[ReportRow], new: &[ReportRow]) -> Vec<ItemChange> {
+    let mut names = old.iter().map(|r| r.item.as_str()).collect::<Vec<_>>();
+    for row in new {
+        if !names.contains(&row.item.as_str()) {
+            names.push(&row.item);
+        }
+    }
+
+    names
+        .into_iter()
+        .flat_map(|item| {
+            let find =
+                |rows: &[ReportRow]| rows.iter().find(|r| r.item == item).map(ReportRow::metrics);
+            let (old, new) = (find(old), find(new));
+            METRICS.iter().enumerate().filter_map(move |(i, metric)| {
+                let (before, after) = (old.map(|m| m[i]), new.map(|m| m[i]));
+                let same = match (before, after) {
+                    (Some(b), Some(a)) => (b - a).abs() < 1e-9 || (b.is_nan() && a.is_nan()),
+                    _ => false,
+                };
+                (!same).then(|| ItemChange {
+                    item: item.to_owned(),
+                    metric,
+                    old: before,
+                    new: after,
+                })
+            })
+        })
+        .collect()
+}
+
+type Key = (String, usize);
+
+// the key respondents are matched on, with how many times it came up before to tell apart
+// respondents sharing one. Both runs are keyed on the first of User ID, timestamp and row that
+// both of them have, since runs read back from json only have rows.
+fn keys(old: &Survey, new: &Survey) -> (Vec<Key>, Vec<Key>) {
+    let filled = |survey: &Survey, column: usize| {
+        !survey.records.is_empty()
+            && survey
+                .records
+                .iter()
+                .all(|r| r.get(column).is_some_and(|c| !c.trim().is_empty()))
+    };
+    let column = [(0, "user"), (2, "submitted")]
+        .into_iter()
+        .find(|(column, _)| filled(old, *column) && filled(new, *column));
+
+    let keys = |survey: &Survey| {
+        let mut seen = BTreeMap::<String, usize>::new();
+        survey
+            .records
+            .iter()
+            .enumerate()
+            .map(|(i, record)| {
+                let key = match column {
+                    Some((column, name)) => {
+                        format!("{name} {}", record.get(column).unwrap_or_default().trim())
+                    }
+                    None => format!("row {}", i + 1),
+                };
+                let count = seen.entry(key.clone()).or_default();
+                *count += 1;
+                (key, *count)
+            })
+            .collect()
+    };
+
+    (keys(old), keys(new))
+}
+
+fn respondents(old: &Survey, new: &Survey, diff: &mut Diff) {
+    let (old_keys, new_keys) = keys(old, new);
+    let name = |(key, count): &Key| {
+        if *count > 1 {
+            format!("{key} ({count})")
+        } else {
+            key.clone()
+        }
+    };
+
+    for (i, key) in old_keys.iter().enumerate() {
+        let Some(j) = new_keys.iter().position(|k| k == key) else {
+            diff.respondents.push(RespondentChange {
+                respondent: name(key),
+                added: false,
+            });
+            continue;
+        };
+
+        let raw = |survey: &Survey, row: usize, item: usize, field: Field| {
+            survey
+                .records
+                .get(row)
+                .and_then(|r| r.get(METADATA_COLUMNS + item * 3 + field.offset()))
+                .unwrap_or_default()
+                .to_owned()
+        };
+        let fruits = old.responses[i]
+            .fruits()
+            .into_iter()
+            .zip(new.responses[j].fruits());
+        for (item, (before, after)) in fruits.enumerate() {
+            for field in [
+                Field::WouldThrow,
+                Field::ExpectedRancidness,
+                Field::DesiredRancidness,
+            ] {
+                let (was, is) = (read_as(before, field), read_as(after, field));
+                if was != is {
+                    diff.cells.push(CellChange {
+                        respondent: name(key),
+                        item: ITEMS[item],
+                        field,
+                        old: was,
+                        new: is,
+                        old_raw: raw(old, i, item, field),
+                        new_raw: raw(new, j, item, field),
+                    });
+                }
+            }
+        }
+    }
+
+    for key in new_keys.iter().filter(|k| !old_keys.contains(k)) {
+        diff.respondents.push(RespondentChange {
+            respondent: name(key),
+            added: true,
+        });
+    }
+}
+
+// what a cell was read as, with why it's missing if it is
+fn read_as(fruit: &Fruit, field: Field) -> String {
+    let question = match field {
+        Field::WouldThrow => return fruit.would_throw.to_string(),
+        Field::ExpectedRancidness => Question::Expected,
+        Field::DesiredRancidness => Question::Desired,
+    };
+    match (fruit.rancidness(question), fruit.missing(question)) {
+        (Some(value), None) => value.to_string(),
+        (Some(value), Some(missing)) => {
+            format!(
+                "{value} (imputed, {})",
+                label(Some(missing)).unwrap_or_default()
+            )
+        }
+        (None, missing) => format!("missing ({})", label(missing).unwrap_or_default()),
+    }
+}
+
+/// Renders the diff as plain text, one change per line.
+pub fn render(diff: &Diff) -> String {
+    if diff.is_empty() {
+        return "no changes\n".to_owned();
+    }
+    let number = |value: Option<f64>| value.map_or_else(|| "-".to_owned(), |v| format!("{v:.2}"));
+
+    let mut text = String::new();
+    if !diff.items.is_empty() {
+        text.push_str("items\n");
+        for change in &diff.items {
+            let delta = match (change.old, change.new) {
+                (Some(old), Some(new)) => format!(" ({:+.2})", new - old),
+                _ => String::new(),
+            };
+            let _ = writeln!(
+                text,
+                "  {} {}: {} -> {}{delta}",
+                change.item,
+                change.metric,
+                number(change.old),
+                number(change.new)
+            );
+        }
+    }
+    if !diff.respondents.is_empty() {
+        text.push_str("respondents\n");
+        for change in &diff.respondents {
+            let _ = writeln!(
+                text,
+                "  {} {}",
+                if change.added { "+" } else { "-" },
+                change.respondent
+            );
+        }
+    }
+    if !diff.cells.is_empty() {
+        text.push_str("cells\n");
+        for change in &diff.cells {
+            let _ = write!(
+                text,
+                "  {} / {} {}: {} -> {}",
+                change.respondent,
+                change.item,
+                label(Some(change.field)).unwrap_or_default(),
+                change.old,
+                change.new
+            );
+            if change.old_raw == change.new_raw && !change.new_raw.is_empty() {
+                let _ = write!(text, " from {:?}", change.new_raw);
+            } else if change.old_raw != change.new_raw {
+                let _ = write!(text, " from {:?} -> {:?}", change.old_raw, change.new_raw);
+            }
+            text.push('\n');
+        }
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::DelimitedSource;
+
+    // a survey with one respondent per row of (User ID, kiwi desired rancidness), everything
+    // else answered with a 3
+    fn survey(rows: &[(&str, &str)]) -> Survey {
+        let headers = ["User ID", "Name", "Timestamp"]
+            .into_iter()
+            .map(str::to_owned)
+            .chain((0..60).map(|i| format!("question {i}")))
+            .collect::<Vec<_>>()
+            .join(",");
+        let kiwi = ITEMS.iter().position(|i| *i == "kiwi").unwrap();
+        let mut csv = headers;
+        for (user, desired) in rows {
+            let answers = (0..20)
+                .map(|i| {
+                    if i == kiwi {
+                        format!("Yes,3,{desired}")
+                    } else {
+                        "Yes,3,3".to_owned()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            csv.push_str(&format!("\n{user},,today,{answers}"));
+        }
+        Survey::from_source(&mut DelimitedSource::new(csv.as_bytes())).unwrap()
+    }
+
+    #[test]
+    fn same_survey_has_no_changes() {
+        let run = || Run::Survey(survey(&[("a", "2"), ("b", "4")]));
+        let diff = diff(&run(), &run(), &Options::default()).unwrap();
+
+        assert!(diff.is_empty());
+        assert_eq!(render(&diff), "no changes\n");
+    }
+
+    #[test]
+    fn finds_moved_numbers_respondents_and_cells() {
+        let old = Run::Survey(survey(&[("a", "2"), ("b", "4")]));
+        let new = Run::Survey(survey(&[("a", "about 5"), ("c", "4")]));
+        let diff = diff(&old, &new, &Options::default()).unwrap();
+
+        let change = diff
+            .items
+            .iter()
+            .find(|c| c.item == "kiwi" && c.metric == "average_desired_rancidness")
+            .unwrap();
+        assert_eq!((change.old, change.new), (Some(3.0), Some(4.5)));
+
+        let respondents = diff
+            .respondents
+            .iter()
+            .map(|r| (r.respondent.as_str(), r.added))
+            .collect::<Vec<_>>();
+        assert_eq!(respondents, [("user b", false), ("user c", true)]);
+
+        assert_eq!(diff.cells.len(), 1);
+        assert_eq!(diff.cells[0].item, "kiwi");
+        assert_eq!(
+            (diff.cells[0].old.as_str(), diff.cells[0].new.as_str()),
+            ("2", "5")
+        );
+        assert_eq!(diff.cells[0].new_raw, "about 5");
+    }
+
+    #[test]
+    fn reports_surveys_with_the_given_weights() {
+        let report = Run::Report(
+            survey(&[("a", "2"), ("b", "4")])
+                .responses
+                .into_iter()
+                .collect::<VecResponse>()
+                .report(&[3.0, 1.0])
+                .iter()
+                .map(ReportRow::from)
+                .collect(),
+        );
+        let run = Run::Survey(survey(&[("a", "2"), ("b", "4")]));
+        let weights = std::env::temp_dir().join(format!(
+            "throw-cruncher-diff-weights-{}.csv",
+            std::process::id()
+        ));
+        std::fs::write(&weights, "User ID,weight\na,3\nb,1\n").unwrap();
+        let weighted = Options {
+            weighting: Weighting::File(weights.clone()),
+            imputation: None,
+        };
+
+        assert!(!diff(&report, &run, &Options::default()).unwrap().is_empty());
+        assert!(diff(&report, &run, &weighted).unwrap().is_empty());
+        std::fs::remove_file(weights).unwrap();
+    }
+}
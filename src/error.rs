@@ -0,0 +1,141 @@
+use std::{fmt, io};
+
+/// Everything that can go wrong while crunching a survey.
+#[derive(Debug)]
+pub enum Error {
+    /// A file couldn't be read or written.
+    Io(io::Error),
+    /// The survey or a side file couldn't be read as csv.
+    Csv(csv::Error),
+    /// The survey couldn't be read as json.
+    Json(serde_json::Error),
+    /// The survey couldn't be read as a spreadsheet.
+    Spreadsheet(calamine::Error),
+    /// The survey source didn't have the expected shape.
+    Source(&'static str),
+    /// A workbook couldn't be written.
+    Xlsx(rust_xlsxwriter::XlsxError),
+    /// An ods workbook couldn't be packaged.
+    Zip(zip::result::ZipError),
+    /// The sqlite export failed.
+    Sqlite(rusqlite::Error),
+    /// The arrow export failed.
+    #[cfg(feature = "columnar")]
+    Arrow(arrow::error::ArrowError),
+    /// The parquet export failed.
+    #[cfg(feature = "columnar")]
+    Parquet(parquet::errors::ParquetError),
+    /// Respondent weights couldn't be worked out.
+    Weight(String),
+    /// The corrections file couldn't be read or applied.
+    Correction(String),
+    /// The notes lexicon couldn't be read.
+    Lexicon(String),
+    /// A json schema couldn't be compiled for validation.
+    Schema(Box<jsonschema::ValidationError<'static>>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "io error: {e}"),
+            Self::Csv(e) => write!(f, "csv error: {e}"),
+            Self::Json(e) => write!(f, "json error: {e}"),
+            Self::Spreadsheet(e) => write!(f, "spreadsheet error: {e}"),
+            Self::Source(reason) => write!(f, "source error: {reason}"),
+            Self::Xlsx(e) => write!(f, "xlsx error: {e}"),
+            Self::Zip(e) => write!(f, "zip error: {e}"),
+            Self::Sqlite(e) => write!(f, "sqlite error: {e}"),
+            #[cfg(feature = "columnar")]
+            Self::Arrow(e) => write!(f, "arrow error: {e}"),
+            #[cfg(feature = "columnar")]
+            Self::Parquet(e) => write!(f, "parquet error: {e}"),
+            Self::Weight(reason) => write!(f, "weighting error: {reason}"),
+            Self::Correction(reason) => write!(f, "correction error: {reason}"),
+            Self::Lexicon(reason) => write!(f, "lexicon error: {reason}"),
+            Self::Schema(e) => write!(f, "schema error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Csv(e) => Some(e),
+            Self::Json(e) => Some(e),
+            Self::Spreadsheet(e) => Some(e),
+            Self::Xlsx(e) => Some(e),
+            Self::Zip(e) => Some(e),
+            Self::Sqlite(e) => Some(e),
+            #[cfg(feature = "columnar")]
+            Self::Arrow(e) => Some(e),
+            #[cfg(feature = "columnar")]
+            Self::Parquet(e) => Some(e),
+            Self::Schema(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(e: csv::Error) -> Self {
+        Self::Csv(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<calamine::Error> for Error {
+    fn from(e: calamine::Error) -> Self {
+        Self::Spreadsheet(e)
+    }
+}
+
+impl From<rust_xlsxwriter::XlsxError> for Error {
+    fn from(e: rust_xlsxwriter::XlsxError) -> Self {
+        Self::Xlsx(e)
+    }
+}
+
+impl From<zip::result::ZipError> for Error {
+    fn from(e: zip::result::ZipError) -> Self {
+        Self::Zip(e)
+    }
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(e: rusqlite::Error) -> Self {
+        Self::Sqlite(e)
+    }
+}
+
+#[cfg(feature = "columnar")]
+impl From<arrow::error::ArrowError> for Error {
+    fn from(e: arrow::error::ArrowError) -> Self {
+        Self::Arrow(e)
+    }
+}
+
+#[cfg(feature = "columnar")]
+impl From<parquet::errors::ParquetError> for Error {
+    fn from(e: parquet::errors::ParquetError) -> Self {
+        Self::Parquet(e)
+    }
+}
+
+impl From<jsonschema::ValidationError<'static>> for Error {
+    fn from(e: jsonschema::ValidationError<'static>) -> Self {
+        Self::Schema(Box::new(e))
+    }
+}
@@ -0,0 +1,244 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    corrections::Field,
+    missing::{Imputation, Missing},
+    normalize::normalize,
+};
+
+/// One respondent's answers about a single item.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Fruit {
+    /// Whether the respondent would throw the item.
+    pub would_throw: bool,
+    /// How rancid the respondent expects the item to be, nominally on a 1-5 scale.
+    pub expected_rancidness: Option<f64>,
+    /// How rancid the respondent would want the item to be, nominally on a 1-5 scale.
+    pub desired_rancidness: Option<f64>,
+    /// Any free text the respondent wrote in place of (or around) a number.
+    pub notes: String,
+    /// Why the expected rancidness couldn't be read, if it couldn't.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_missing: Option<Missing>,
+    /// Why the desired rancidness couldn't be read, if it couldn't.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desired_missing: Option<Missing>,
+    /// The strategy used to fill in a missing rancidness, if one was.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub imputation: Option<Imputation>,
+    /// The answers that were read from a hand correction rather than the raw export.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub corrected: Vec<Field>,
+}
+
+/// The two rancidness questions asked about every item.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Question {
+    Expected,
+    Desired,
+}
+
+impl Question {
+    pub const ALL: [Self; 2] = [Self::Expected, Self::Desired];
+}
+
+impl Fruit {
+    /// Reads the would-throw, expected and desired cells for one item off the front of `iter`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_iter<'r>(iter: &mut impl Iterator<Item = &'r str>) -> Result<Self, &'static str> {
+        let mut notes = String::new();
+        let would_throw = parse_bool(iter.next().ok_or("end of row")?)?;
+        // cells are read normalized, but kept in the notes as they were written
+        let cell = iter.next().ok_or("end of row")?;
+        let (expected_rancidness, expected_missing) =
+            match best_effort_parse_float(&normalize(cell)) {
+                Ok(FloatNote::Float(f)) => (Some(f), None),
+                Ok(FloatNote::FloatNote(f)) => {
+                    notes.push_str(cell);
+                    (Some(f), None)
+                }
+                Err(_) => {
+                    notes.push_str(cell);
+
+                    // checked on what was written, so no corrected word can turn into "fresh"
+                    if cell.to_lowercase().contains("fresh") {
+                        // some chuckleheads decided to use the word "fresh" instead of 1 on the scale.
+                        // if we see fresh in the string, just assume they meant 1.
+                        // it shouldn't mess the data up too bad :)
+                        (Some(1.0), None)
+                    } else {
+                        (None, Some(Missing::classify(cell)))
+                    }
+                }
+            };
+        let separator = if notes.is_empty() { "" } else { " | " };
+        let cell = iter.next().ok_or("end of row")?;
+        let (desired_rancidness, desired_missing) = match best_effort_parse_float(&normalize(cell))
+        {
+            Ok(FloatNote::Float(f)) => (Some(f), None),
+            Ok(FloatNote::FloatNote(f)) => {
+                notes.push_str(separator);
+                notes.push_str(cell);
+                (Some(f), None)
+            }
+            Err(_) => {
+                notes.push_str(separator);
+                notes.push_str(cell);
+
+                if cell.to_lowercase().contains("fresh") {
+                    // some chuckleheads decided to use the word "fresh" instead of 1 on the scale.
+                    // if we see fresh in the string, just assume they meant 1.
+                    // it shouldn't mess the data up too bad :)
+                    (Some(1.0), None)
+                } else {
+                    (None, Some(Missing::classify(cell)))
+                }
+            }
+        };
+        Ok(Self {
+            would_throw,
+            expected_rancidness,
+            desired_rancidness,
+            notes,
+            expected_missing,
+            desired_missing,
+            imputation: None,
+            corrected: Vec::new(),
+        })
+    }
+
+    /// Whether any answer had to be coerced from text or fell outside the 1-5 scale.
+    pub fn is_coerced(&self) -> bool {
+        let out_of_range = |f: Option<f64>| f.is_some_and(|f| !(1.0..=5.0).contains(&f));
+
+        !self.notes.is_empty()
+            || out_of_range(self.expected_rancidness)
+            || out_of_range(self.desired_rancidness)
+    }
+
+    /// Clamps both rancidness answers onto the 1-5 scale.
+    pub fn massage(self) -> Self {
+        Self {
+            would_throw: self.would_throw,
+            expected_rancidness: self.expected_rancidness.map(|f| f.clamp(1.0, 5.0)),
+            desired_rancidness: self.desired_rancidness.map(|f| f.clamp(1.0, 5.0)),
+            notes: self.notes,
+            expected_missing: self.expected_missing,
+            desired_missing: self.desired_missing,
+            imputation: self.imputation,
+            corrected: self.corrected,
+        }
+    }
+
+    pub fn rancidness(&self, question: Question) -> Option<f64> {
+        match question {
+            Question::Expected => self.expected_rancidness,
+            Question::Desired => self.desired_rancidness,
+        }
+    }
+
+    pub fn rancidness_mut(&mut self, question: Question) -> &mut Option<f64> {
+        match question {
+            Question::Expected => &mut self.expected_rancidness,
+            Question::Desired => &mut self.desired_rancidness,
+        }
+    }
+
+    /// Whether the answer to `question` was missing but filled in by imputation.
+    pub fn is_imputed(&self, question: Question) -> bool {
+        self.rancidness(question).is_some() && self.missing(question).is_some()
+    }
+
+    /// Whether either rancidness answer was a refusal to throw the item at all.
+    pub fn refused(&self) -> bool {
+        Question::ALL.into_iter().any(|q| self.refused_on(q))
+    }
+
+    /// Whether the answer to `question` was a refusal to throw the item at all.
+    pub fn refused_on(&self, question: Question) -> bool {
+        matches!(self.missing(question), Some(Missing::Refused))
+    }
+
+    pub fn missing(&self, question: Question) -> Option<Missing> {
+        match question {
+            Question::Expected => self.expected_missing,
+            Question::Desired => self.desired_missing,
+        }
+    }
+}
+
+fn parse_bool(input: &str) -> Result<bool, &'static str> {
+    match input {
+        "Yes" => Ok(true),
+        "No" => Ok(false),
+        _ => Err(format!("malformed bool: {input}").leak()),
+    }
+}
+
+// a number read cleanly, or one picked out of surrounding text
+enum FloatNote {
+    Float(f64),
+    FloatNote(f64),
+}
+
+fn best_effort_parse_float(input: &str) -> Result<FloatNote, &str> {
+    static REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"([-]?[0-9]*\.?,?[0-9]+)").unwrap());
+
+    // "nan" and "inf" parse as floats, but aren't answers anyone gave on a 1-5 scale
+    if let Ok(result) = input.parse::<f64>()
+        && result.is_finite()
+    {
+        Ok(FloatNote::Float(result))
+    } else if let Some(captures) = REGEX.captures(input) {
+        let capture = captures.get(0).unwrap();
+        Ok(FloatNote::FloatNote(capture.as_str().parse().unwrap()))
+    } else {
+        Err(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fruit(cells: &[&str]) -> Result<Fruit, &'static str> {
+        Fruit::from_iter(&mut cells.iter().copied())
+    }
+
+    #[test]
+    fn reads_clean_and_noted_numbers() {
+        let fruit = fruit(&["Yes", "3", "about 4 I guess"]).unwrap();
+        assert!(fruit.would_throw);
+        assert_eq!(fruit.expected_rancidness, Some(3.0));
+        assert_eq!(fruit.desired_rancidness, Some(4.0));
+        assert_eq!(fruit.notes, "about 4 I guess");
+    }
+
+    #[test]
+    fn rejects_non_finite_numbers() {
+        let fruit = fruit(&["No", "NaN", "inf"]).unwrap();
+        assert_eq!(fruit.expected_rancidness, None);
+        assert_eq!(fruit.desired_rancidness, None);
+        assert!(fruit.expected_missing.is_some());
+        assert!(fruit.desired_missing.is_some());
+    }
+
+    #[test]
+    fn reads_fresh_as_one_only_when_written() {
+        let fruit = fruit(&["Yes", "so fresh", "all flesh"]).unwrap();
+        assert_eq!(fruit.expected_rancidness, Some(1.0));
+        assert_eq!(fruit.desired_rancidness, None);
+    }
+
+    #[test]
+    fn rejects_malformed_bools() {
+        assert!(fruit(&["maybe", "1", "1"]).is_err());
+        assert!(fruit(&["Yes", "1"]).is_err());
+    }
+}
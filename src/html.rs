@@ -0,0 +1,150 @@
+//! Renders the report as a single static html page.
+//!
+//! Charts are inline svg and styles are inline css, so the page has no external assets and
+//! reads the same offline or as an email attachment.
+
+use std::fmt::Write as _;
+
+use crate::{
+    ItemReport, Reject, VecResponse, display_name, escape, flags, label, missing::MissingRow,
+    ranking, svg,
+};
+
+const STYLE: &str = "
+body { font-family: sans-serif; max-width: 60em; margin: 2em auto; padding: 0 1em; color: #222; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { padding: 0.2em 0.6em; border-bottom: 1px solid #ddd; text-align: right; }
+th:first-child, td:first-child { text-align: left; }
+tr.flagged td { background: #fff3cd; }
+.items { display: flex; flex-wrap: wrap; gap: 1em; }
+.item { flex: 1 1 18em; }
+.item ul { font-size: 0.9em; color: #555; }
+";
+
+/// Renders everything into one html document: the ranking, a summary table, the charts, each
+/// item's notes and the data quality flags.
+///
+/// `missing` is the [`missing::table`](crate::missing::table) of `vec_response`, and
+/// `reports` its [`VecResponse::report`].
+pub fn render(
+    vec_response: &VecResponse,
+    reports: &[ItemReport; 20],
+    missing: &[MissingRow],
+    rejects: &[Reject],
+) -> String {
+    let mut html = format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Throw survey report</title>
+<style>{STYLE}</style>
+</head>
+<body>
+<h1>Throw survey report</h1>
+<p>{} responses, {} rejected rows.</p>
+"#,
+        vec_response.len(),
+        rejects.len()
+    );
+
+    html.push_str("<h2>Ranking</h2>\n<ol>\n");
+    for report in ranking(reports) {
+        let _ = writeln!(
+            html,
+            "<li>{} ({:.0}% would throw)</li>",
+            escape(&display_name(report.item)),
+            report.throw_share() * 100.0
+        );
+    }
+    html.push_str("</ol>\n");
+
+    html.push_str("<h2>Summary</h2>\n<table>\n<tr><th>Item</th><th>Would throw</th><th>Would not throw</th><th>Throw %</th><th>Expected rancidness</th><th>Desired rancidness</th><th>Gap</th><th>Effective n</th><th>Flags</th></tr>\n");
+    for report in reports {
+        let flags = flags(report, missing);
+        let _ = writeln!(
+            html,
+            "<tr{}><td>{}</td><td>{:.1}</td><td>{:.1}</td><td>{:.0}%</td><td>{:.2}</td><td>{:.2}</td><td>{:+.2}</td><td>{:.1}</td><td>{}</td></tr>",
+            if flags.is_empty() {
+                ""
+            } else {
+                r#" class="flagged""#
+            },
+            escape(&display_name(report.item)),
+            report.weighted_would_throw_count,
+            report.weighted_would_not_throw_count,
+            report.throw_share() * 100.0,
+            report.average_expected_rancidness,
+            report.average_desired_rancidness,
+            report.rancidness_gap(),
+            report.effective_sample_size,
+            flags
+                .iter()
+                .map(|f| f.describe())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>Expected vs desired rancidness</h2>\n");
+    html.push_str(&svg::dumbbell(reports));
+
+    html.push_str("\n<h2>Items</h2>\n<div class=\"items\">\n");
+    for (report, (_, fruits)) in reports.iter().zip(vec_response.items()) {
+        let _ = write!(
+            html,
+            "<section class=\"item\">\n<h3>{}</h3>\n{}\n",
+            escape(&display_name(report.item)),
+            svg::histogram(report)
+        );
+        let notes = fruits
+            .iter()
+            .map(|f| f.notes.trim())
+            .filter(|n| !n.is_empty())
+            .collect::<Vec<_>>();
+        if !notes.is_empty() {
+            html.push_str("<ul>\n");
+            for note in notes {
+                let _ = writeln!(html, "<li>{}</li>", escape(note));
+            }
+            html.push_str("</ul>\n");
+        }
+        html.push_str("</section>\n");
+    }
+    html.push_str("</div>\n");
+
+    html.push_str("<h2>Data quality</h2>\n<table>\n<tr><th>Item</th><th>Question</th><th>Answered</th><th>Blank</th><th>Text</th><th>Unparseable</th><th>Refused</th><th>Missing</th><th>Imputed</th></tr>\n");
+    for row in missing {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.0}%</td><td>{}</td></tr>",
+            escape(&display_name(row.item)),
+            label(Some(row.question)).unwrap_or_default(),
+            row.answered,
+            row.blank,
+            row.text,
+            row.unparseable,
+            row.refused,
+            row.missing_share * 100.0,
+            row.imputed
+        );
+    }
+    html.push_str("</table>\n");
+
+    if !rejects.is_empty() {
+        html.push_str("<h3>Rejected rows</h3>\n<table>\n<tr><th>Line</th><th>Reason</th></tr>\n");
+        for reject in rejects {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td></tr>",
+                reject.line,
+                escape(reject.reason)
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
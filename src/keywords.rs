@@ -0,0 +1,249 @@
+//! Term frequencies over the free-text notes: which words and word pairs come up, overall and
+//! per item, and which ones are distinctive to an item's notes.
+
+use std::collections::BTreeMap;
+
+use rust_stemmers::{Algorithm, Stemmer};
+use serde::Serialize;
+
+use crate::{VecResponse, normalize::normalize};
+
+/// Words too common to say anything about an item, dropped before counting.
+pub const STOP_WORDS: &[&str] = &[
+    "a", "about", "above", "after", "again", "all", "am", "an", "and", "any", "are", "as", "at",
+    "b4", "be", "because", "been", "being", "both", "but", "by", "can", "could", "did", "do",
+    "does", "doing", "dont", "down", "each", "either", "else", "even", "ever", "for", "from",
+    "get", "had", "has", "have", "having", "he", "her", "here", "him", "his", "how", "i", "if",
+    "im", "in", "into", "is", "it", "its", "just", "like", "me", "more", "most", "my", "no", "nor",
+    "not", "of", "off", "on", "once", "one", "only", "or", "other", "our", "out", "over", "same",
+    "so", "some", "such", "than", "that", "the", "their", "them", "then", "there", "these", "they",
+    "this", "those", "through", "to", "too", "under", "until", "up", "very", "was", "we", "were",
+    "what", "when", "where", "which", "while", "who", "why", "will", "with", "would", "you",
+    "your",
+];
+
+/// How often one term (a word, or a pair of adjacent words) comes up.
+#[derive(Debug, Clone, Serialize)]
+pub struct Term {
+    /// The item whose notes were counted, or `overall` across every item.
+    pub item: &'static str,
+    /// The term as it was most often written.
+    pub term: String,
+    /// The stemmed form occurrences were counted under.
+    pub stem: String,
+    /// 1 for a single word, 2 for a pair of adjacent words.
+    pub words: usize,
+    pub count: usize,
+    /// How many notes the term comes up in.
+    pub notes: usize,
+    /// How distinctive the term is to the item, treating each item's notes as one document.
+    /// Empty for the overall counts.
+    pub tf_idf: Option<f64>,
+}
+
+// every word of a note in order, as (stem, as written), with `None` in place of the words not
+// worth counting so that only words written side by side pair up
+fn tokenize(stemmer: &Stemmer, note: &str) -> Vec<Option<(String, String)>> {
+    normalize(note)
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|word| word.trim_matches('\'').to_lowercase().replace('\'', ""))
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            (word.chars().count() > 1
+                && !word.chars().all(|c| c.is_ascii_digit())
+                && !STOP_WORDS.contains(&word.as_str()))
+            .then(|| (stemmer.stem(&word).into_owned(), word))
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct Tally {
+    count: usize,
+    notes: usize,
+    // how often each written form of the stem came up
+    forms: BTreeMap<String, usize>,
+}
+
+impl Tally {
+    fn term(&self) -> String {
+        // the most common form, ties going to the alphabetically first
+        self.forms
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+            .map(|(form, _)| form.clone())
+            .unwrap_or_default()
+    }
+}
+
+// every unigram and bigram in `notes`, keyed by words then stem
+fn tally<'n>(
+    stemmer: &Stemmer,
+    notes: impl IntoIterator<Item = &'n str>,
+) -> BTreeMap<(usize, String), Tally> {
+    let mut tallies = BTreeMap::<(usize, String), Tally>::new();
+
+    for note in notes {
+        let tokens = tokenize(stemmer, note);
+        let unigrams = tokens
+            .iter()
+            .flatten()
+            .map(|(stem, word)| (1, stem.clone(), word.clone()));
+        // "throw it away" has no bigram, rather than "throw away"
+        let bigrams = tokens.windows(2).filter_map(|pair| {
+            let [Some(first), Some(second)] = pair else {
+                return None;
+            };
+            Some((
+                2,
+                format!("{} {}", first.0, second.0),
+                format!("{} {}", first.1, second.1),
+            ))
+        });
+
+        let mut seen = Vec::new();
+        for (words, stem, form) in unigrams.chain(bigrams) {
+            let tally = tallies.entry((words, stem.clone())).or_default();
+            tally.count += 1;
+            *tally.forms.entry(form).or_default() += 1;
+            if !seen.contains(&(words, stem.clone())) {
+                tally.notes += 1;
+                seen.push((words, stem));
+            }
+        }
+    }
+
+    tallies
+}
+
+/// Counts the words and word pairs in every item's notes, keeping the `top` unigrams and
+/// bigrams per item by tf-idf, and the `top` of each overall by count.
+///
+/// Words are normalized (see [`normalize`]), stop words dropped and the rest stemmed, so
+/// "tomato" and "tomatoes" count together. Bigrams are only the words written side by side, so
+/// none spans a dropped stop word.
+pub fn table(vec_response: &VecResponse, top: usize) -> Vec<Term> {
+    let stemmer = Stemmer::create(Algorithm::English);
+    let items = vec_response.items();
+
+    let per_item = items
+        .iter()
+        .map(|(item, fruits)| {
+            let notes = fruits
+                .iter()
+                .map(|f| f.notes.trim())
+                .filter(|n| !n.is_empty());
+            (*item, tally(&stemmer, notes))
+        })
+        .collect::<Vec<_>>();
+    let overall = tally(
+        &stemmer,
+        items
+            .iter()
+            .flat_map(|(_, fruits)| fruits.iter().map(|f| f.notes.trim()))
+            .filter(|n| !n.is_empty()),
+    );
+
+    // an item is a document here, so idf is about how many items' notes use the term
+    let documents = per_item.iter().filter(|(_, t)| !t.is_empty()).count() as f64;
+    let idf = |key: &(usize, String)| {
+        let containing = per_item.iter().filter(|(_, t)| t.contains_key(key)).count() as f64;
+        ((1.0 + documents) / (1.0 + containing)).ln() + 1.0
+    };
+
+    let mut terms = Vec::new();
+    for (item, tallies) in &per_item {
+        for words in [1, 2] {
+            let total = tallies
+                .iter()
+                .filter(|((w, _), _)| *w == words)
+                .map(|(_, t)| t.count)
+                .sum::<usize>() as f64;
+            let mut scored = tallies
+                .iter()
+                .filter(|((w, _), _)| *w == words)
+                .map(|(key, tally)| Term {
+                    item,
+                    term: tally.term(),
+                    stem: key.1.clone(),
+                    words,
+                    count: tally.count,
+                    notes: tally.notes,
+                    tf_idf: Some(tally.count as f64 / total * idf(key)),
+                })
+                .collect::<Vec<_>>();
+            scored.sort_by(|a, b| {
+                b.tf_idf
+                    .partial_cmp(&a.tf_idf)
+                    .unwrap()
+                    .then(b.count.cmp(&a.count))
+                    .then(a.stem.cmp(&b.stem))
+            });
+            terms.extend(scored.into_iter().take(top));
+        }
+    }
+
+    for words in [1, 2] {
+        let mut counted = overall
+            .iter()
+            .filter(|((w, _), _)| *w == words)
+            .map(|((_, stem), tally)| Term {
+                item: "overall",
+                term: tally.term(),
+                stem: stem.clone(),
+                words,
+                count: tally.count,
+                notes: tally.notes,
+                tf_idf: None,
+            })
+            .collect::<Vec<_>>();
+        counted.sort_by(|a, b| b.count.cmp(&a.count).then(a.stem.cmp(&b.stem)));
+        terms.extend(counted.into_iter().take(top));
+    }
+
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenizes_without_stop_words() {
+        let stemmer = Stemmer::create(Algorithm::English);
+        let words = tokenize(&stemmer, "I'd throw 2 tomatoes, it's the tastiest")
+            .into_iter()
+            .flatten()
+            .map(|(_, word)| word)
+            .collect::<Vec<_>>();
+
+        assert!(!words.contains(&"2".to_owned()));
+        assert!(words.contains(&"tomatoes".to_owned()));
+        assert!(words.contains(&"tastiest".to_owned()));
+    }
+
+    #[test]
+    fn tallies_stems_once_per_note() {
+        let stemmer = Stemmer::create(Algorithm::English);
+        let tallies = tally(&stemmer, ["tomato tomatoes", "tomato"]);
+        let tomato = &tallies[&(1, stemmer.stem("tomato").into_owned())];
+
+        assert_eq!(tomato.count, 3);
+        assert_eq!(tomato.notes, 2);
+        assert_eq!(tomato.term(), "tomato");
+        assert_eq!(tallies.keys().filter(|(words, _)| *words == 2).count(), 1);
+    }
+
+    #[test]
+    fn bigrams_never_span_stop_words() {
+        let stemmer = Stemmer::create(Algorithm::English);
+        let tallies = tally(&stemmer, ["throw it away", "rotten tomato"]);
+        let bigrams = tallies
+            .iter()
+            .filter(|((words, _), _)| *words == 2)
+            .map(|(_, tally)| tally.term())
+            .collect::<Vec<_>>();
+
+        assert_eq!(bigrams, ["rotten tomato"]);
+    }
+}
@@ -0,0 +1,159 @@
+//! Renders the report as `booktabs` LaTeX tables, for dropping into a write-up.
+//!
+//! The output is a fragment rather than a whole document: three `table` floats (the summary,
+//! the ranking and the gap tests) that need `\usepackage{booktabs}` in the preamble.
+
+use std::fmt::Write as _;
+
+use crate::{GapTest, ItemReport, display_name, ranking};
+
+/// Renders the summary, ranking and gap test tables, with `precision` decimal places on
+/// every fractional number.
+pub fn render(reports: &[ItemReport; 20], gap_tests: &[GapTest; 20], precision: usize) -> String {
+    let fixed = |value: f64| fixed(value, precision);
+    let mut latex = String::new();
+
+    open(
+        &mut latex,
+        "Would-throw counts and mean rancidness per item.",
+        "throw-summary",
+        "lrrrrrr",
+    );
+    latex.push_str("Item & Throw & Not & Throw (\\%) & Expected & Desired & $n_\\mathrm{eff}$ \\\\\n\\midrule\n");
+    for report in reports {
+        let _ = writeln!(
+            latex,
+            "{} & {} & {} & {} & {} & {} & {} \\\\",
+            escape(&display_name(report.item)),
+            count(report.weighted_would_throw_count, precision),
+            count(report.weighted_would_not_throw_count, precision),
+            fixed(report.throw_share() * 100.0),
+            fixed(report.average_expected_rancidness),
+            fixed(report.average_desired_rancidness),
+            fixed(report.effective_sample_size)
+        );
+    }
+    close(&mut latex);
+
+    open(
+        &mut latex,
+        "Items ranked by the share of respondents who would throw them.",
+        "throw-ranking",
+        "rlrr",
+    );
+    latex.push_str("Rank & Item & Throw (\\%) & Desired \\\\\n\\midrule\n");
+    for (rank, report) in (1..).zip(ranking(reports)) {
+        let _ = writeln!(
+            latex,
+            "{rank} & {} & {} & {} \\\\",
+            escape(&display_name(report.item)),
+            fixed(report.throw_share() * 100.0),
+            fixed(report.average_desired_rancidness)
+        );
+    }
+    close(&mut latex);
+
+    open(
+        &mut latex,
+        "Paired $t$-tests of desired minus expected rancidness.",
+        "throw-gap-tests",
+        "lrrrrrr",
+    );
+    latex.push_str("Item & Pairs & Mean gap & SE & $t$ & df & $p$ \\\\\n\\midrule\n");
+    for test in gap_tests {
+        let _ = writeln!(
+            latex,
+            "{} & {} & {} & {} & {} & {} & {} \\\\",
+            escape(&display_name(test.item)),
+            test.pairs,
+            fixed(test.mean_gap),
+            fixed(test.standard_error),
+            fixed(test.t),
+            fixed(test.degrees_of_freedom),
+            p_value(test.p_value, precision)
+        );
+    }
+    close(&mut latex);
+
+    latex
+}
+
+fn open(latex: &mut String, caption: &str, label: &str, columns: &str) {
+    let _ = write!(
+        latex,
+        "\\begin{{table}}[htbp]\n\\centering\n\\caption{{{caption}}}\n\\label{{tab:{label}}}\n\\begin{{tabular}}{{{columns}}}\n\\toprule\n"
+    );
+}
+
+fn close(latex: &mut String) {
+    latex.push_str("\\bottomrule\n\\end{tabular}\n\\end{table}\n\n");
+}
+
+// a dash for anything that couldn't be computed
+fn fixed(value: f64, precision: usize) -> String {
+    if value.is_finite() {
+        format!("{value:.precision$}")
+    } else {
+        "--".to_owned()
+    }
+}
+
+// whole numbers for plain counts, only going fractional once weights make them so
+fn count(value: f64, precision: usize) -> String {
+    if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        fixed(value, precision)
+    }
+}
+
+// p-values never get fewer decimal places than this, so a tiny one can't read as "<1"
+const P_VALUE_PRECISION: usize = 3;
+
+// p-values too small to show at this precision read as an upper bound instead of zero
+fn p_value(p: f64, precision: usize) -> String {
+    let precision = precision.max(P_VALUE_PRECISION);
+    let smallest = 10f64.powi(-(precision as i32));
+    if p.is_finite() && p < smallest {
+        format!("$<{}$", fixed(smallest, precision))
+    } else {
+        fixed(p, precision)
+    }
+}
+
+// item names and the like, with every character LaTeX treats specially made literal
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_p_values_keep_a_minimum_precision() {
+        assert_eq!(p_value(0.00001, 0), "$<0.001$");
+        assert_eq!(p_value(0.00001, 4), "$<0.0001$");
+        assert_eq!(p_value(0.25, 0), "0.250");
+        assert_eq!(p_value(f64::NAN, 2), "--");
+    }
+
+    #[test]
+    fn counts_are_whole_until_weighted() {
+        assert_eq!(count(12.0, 2), "12");
+        assert_eq!(count(12.5, 2), "12.50");
+    }
+}
@@ -0,0 +1,267 @@
+//! Crunches the throw survey: which produce people would throw, and how rancid they expect
+//! and want it to be.
+//!
+//! A [`Survey`] is ingested from the survey export, massaged onto the 1-5 scale and then
+//! reported on item by item:
+//!
+//! ```no_run
+//! use throw_cruncher::{Survey, Weighting};
+//!
+//! let survey = Survey::from_path("throwcsv.csv")?;
+//! let weights = survey.weights(&Weighting::Uniform)?;
+//! let reports = survey.massage().into_vec_response().report(&weights);
+//! # Ok::<(), throw_cruncher::Error>(())
+//! ```
+
+pub mod codebook;
+#[cfg(feature = "columnar")]
+pub mod columnar;
+pub mod corrections;
+pub mod diff;
+mod error;
+mod fruit;
+pub mod html;
+pub mod keywords;
+pub mod latex;
+pub mod markdown;
+pub mod missing;
+pub mod normalize;
+pub mod redact;
+mod report;
+mod response;
+pub mod schema;
+pub mod sentiment;
+pub mod source;
+pub mod sqlite;
+pub mod svg;
+pub mod terminal;
+pub mod themes;
+pub mod waves;
+pub mod weights;
+pub mod workbook;
+
+use std::{fs::File, io, path::Path};
+
+use corrections::{Correction, Overlay, Override};
+use csv::{Reader, StringRecord};
+use redact::Redaction;
+use serde::Serialize;
+use source::{DelimitedSource, Sheet};
+
+pub use error::Error;
+pub use fruit::{Fruit, Question};
+pub use missing::{Imputation, Missing};
+pub use report::{
+    Flag, FlattenedReport, GapTest, HistogramRow, ItemReport, flags, gap_test, ranking, report,
+};
+pub use response::{FlattenedResponse, ITEMS, Response, VecResponse, display_name};
+pub use source::{Format, ResponseSource};
+pub use weights::Weighting;
+
+/// The number of leading columns (User ID, display name, timestamp) before the item columns.
+pub const METADATA_COLUMNS: usize = 3;
+
+/// A survey export: the raw rows alongside the responses parsed from them.
+#[derive(Debug)]
+pub struct Survey {
+    /// The header row, holding the question text for every column.
+    pub headers: StringRecord,
+    /// The raw rows, in the same order as `responses`.
+    pub records: Vec<StringRecord>,
+    /// The parsed responses, one per row.
+    pub responses: Vec<Response>,
+    /// The rows that couldn't be parsed, set aside rather than failing the whole ingest.
+    pub rejects: Vec<Reject>,
+    /// Every hand correction applied on top of the raw rows.
+    pub corrections: Vec<Correction>,
+    /// Everything scrubbed out for publishing, see [`Survey::redact`].
+    pub redactions: Vec<Redaction>,
+}
+
+/// A survey row that couldn't be parsed into a [`Response`].
+#[derive(Debug, Serialize)]
+pub struct Reject {
+    /// The line the row started on.
+    pub line: u64,
+    /// Why the row couldn't be parsed.
+    pub reason: &'static str,
+    /// The raw row.
+    #[serde(skip)]
+    pub record: StringRecord,
+}
+
+impl Survey {
+    /// Ingests a survey export from a file, picking the format by extension.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        match Format::from_path(path) {
+            Format::Responses => Self::from_json(File::open(path)?),
+            _ => Self::from_source(source::open(path, None, &Sheet::default())?.as_mut()),
+        }
+    }
+
+    /// Reads back a json array of responses the cruncher wrote out, like a hand-corrected
+    /// `result_ingested.json` or `result_massaged.json`, see [`Survey::from_responses`].
+    pub fn from_json(reader: impl io::Read) -> Result<Self, Error> {
+        Ok(Self::from_responses(serde_json::from_reader(reader)?))
+    }
+
+    /// Rebuilds a survey from already parsed responses.
+    ///
+    /// The original rows are long gone, so the headers are the flattened column names (with
+    /// empty metadata columns) and each record is rebuilt from its response's values.
+    pub fn from_responses(responses: Vec<Response>) -> Self {
+        let headers = ["User ID", "User display name", "Timestamp"]
+            .into_iter()
+            .map(str::to_owned)
+            .chain(ITEMS.iter().flat_map(|item| {
+                [
+                    format!("{item}_would_throw"),
+                    format!("{item}_expected_rancidness"),
+                    format!("{item}_desired_rancidness"),
+                ]
+            }))
+            .collect();
+        let records = responses
+            .iter()
+            .map(|response| {
+                let number = |f: Option<f64>| f.map(|f| f.to_string()).unwrap_or_default();
+                ["", "", ""]
+                    .into_iter()
+                    .map(str::to_owned)
+                    .chain(response.fruits().into_iter().flat_map(|fruit| {
+                        [
+                            if fruit.would_throw { "Yes" } else { "No" }.to_owned(),
+                            number(fruit.expected_rancidness),
+                            number(fruit.desired_rancidness),
+                        ]
+                    }))
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            headers,
+            records,
+            responses,
+            rejects: Vec::new(),
+            corrections: Vec::new(),
+            redactions: Vec::new(),
+        }
+    }
+
+    /// Ingests a survey export from an already opened csv reader.
+    pub fn from_csv<R: io::Read>(reader: Reader<R>) -> Result<Self, Error> {
+        Self::from_source(&mut DelimitedSource::from_csv(reader))
+    }
+
+    /// Ingests a survey export from any [`ResponseSource`].
+    pub fn from_source(source: &mut dyn ResponseSource) -> Result<Self, Error> {
+        Self::from_source_with_overrides(source, &[])
+    }
+
+    /// Ingests a survey export from any [`ResponseSource`], reading each row with the matching
+    /// `overrides` patched in. `records` keeps the rows as they were exported, and every
+    /// override applied is listed in `corrections` and marked on the corrected [`Fruit`].
+    ///
+    /// An override that matches no row is an error, since it's most likely a typo.
+    pub fn from_source_with_overrides(
+        source: &mut dyn ResponseSource,
+        overrides: &[Override],
+    ) -> Result<Self, Error> {
+        let headers = source.headers()?;
+
+        let mut overlay = Overlay::new(overrides);
+        let mut records = Vec::new();
+        let mut responses = Vec::new();
+        let mut rejects = Vec::new();
+        let mut corrections = Vec::new();
+        let mut row = 0;
+        while let Some(record) = source.next_row().transpose()? {
+            row += 1;
+            let (patched, applied) = overlay.apply(row, &record);
+
+            match Response::from_iter(&mut patched.iter().skip(METADATA_COLUMNS)) {
+                Ok(mut response) => {
+                    let fruits = response.fruits_mut();
+                    for correction in &applied {
+                        if let Some(i) = ITEMS.iter().position(|i| *i == correction.item) {
+                            fruits[i].corrected.push(correction.field);
+                        }
+                    }
+                    records.push(record);
+                    responses.push(response);
+                }
+                Err(reason) => rejects.push(Reject {
+                    line: record.position().map_or(0, |p| p.line()),
+                    reason,
+                    record,
+                }),
+            }
+            corrections.extend(applied);
+        }
+
+        if let Some(o) = overlay.unused() {
+            return Err(Error::Correction(format!(
+                "no row matches the correction to {} for {}",
+                o.item,
+                o.target()
+            )));
+        }
+
+        Ok(Self {
+            headers,
+            records,
+            responses,
+            rejects,
+            corrections,
+            redactions: Vec::new(),
+        })
+    }
+
+    /// Works out every respondent's weight, in the same order as `responses`.
+    pub fn weights(&self, weighting: &Weighting) -> Result<Vec<f64>, Error> {
+        match weighting {
+            Weighting::Uniform => Ok(vec![1.0; self.responses.len()]),
+            Weighting::Column(column) => weights::from_column(&self.headers, &self.records, column),
+            Weighting::File(path) => weights::from_file(path, &self.records),
+            Weighting::Quality => Ok(weights::from_quality(&self.responses)),
+        }
+        .and_then(weights::validate)
+        .map_err(Error::Weight)
+    }
+
+    /// Massages every response, see [`Response::massage`].
+    pub fn massage(self) -> Self {
+        Self {
+            responses: self.responses.into_iter().map(Response::massage).collect(),
+            ..self
+        }
+    }
+
+    /// Fills in missing rancidness answers, see [`Imputation`].
+    pub fn impute(&mut self, imputation: Imputation) {
+        missing::impute(&mut self.responses, imputation);
+    }
+
+    /// Regroups the responses by item, ready for reporting.
+    pub fn into_vec_response(self) -> VecResponse {
+        self.responses.into_iter().collect()
+    }
+}
+
+// the same snake_case name an enum value has in the json outputs, for formats that store it
+// as plain text
+pub(crate) fn label(value: Option<impl Serialize>) -> Option<String> {
+    value
+        .and_then(|v| serde_json::to_value(v).ok())
+        .and_then(|v| v.as_str().map(str::to_owned))
+}
+
+// escapes text for xml and html alike
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -1,763 +1,652 @@
-use std::{fs::File, iter::once, num::ParseFloatError, ops::Not, sync::LazyLock};
-
-use csv::{Reader, Writer};
-use regex::Regex;
-use serde::Serialize;
-
-#[derive(Debug, Serialize)]
-struct Fruit {
-    would_throw: bool,
-    expected_rancidness: Option<f64>,
-    desired_rancidness: Option<f64>,
-    notes: String,
-}
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    process,
+};
 
-impl Fruit {
-    fn from_iter<'r>(iter: &mut impl Iterator<Item = &'r str>) -> Result<Self, &'static str> {
-        let mut notes = String::new();
-        let would_throw = parse_bool(iter.next().ok_or("end of row")?)?;
-        let expected_rancidness = match best_effort_parse_float(iter.next().ok_or("end of row")?) {
-            Ok(FloatNote::Float(f)) => Some(f),
-            Ok(FloatNote::FloatNote(f, note)) => {
-                notes.push_str(note);
-                Some(f)
-            }
-            Err(note) => {
-                notes.push_str(note);
-
-                if note.to_ascii_lowercase().contains("fresh") {
-                    // some chuckleheads decided to use the word "fresh" instead of 1 on the scale.
-                    // if we see fresh in the string, just assume they meant 1.
-                    // it shouldn't mess the data up too bad :)
-                    Some(1.0)
-                } else {
-                    None
-                }
-            }
-        };
-        let separator = if notes.is_empty() { "" } else { " | " };
-        let desired_rancidness = match best_effort_parse_float(iter.next().ok_or("end of row")?) {
-            Ok(FloatNote::Float(f)) => Some(f),
-            Ok(FloatNote::FloatNote(f, note)) => {
-                notes.push_str(separator);
-                notes.push_str(note);
-                Some(f)
-            }
-            Err(note) => {
-                notes.push_str(separator);
-                notes.push_str(note);
-
-                if note.to_ascii_lowercase().contains("fresh") {
-                    // some chuckleheads decided to use the word "fresh" instead of 1 on the scale.
-                    // if we see fresh in the string, just assume they meant 1.
-                    // it shouldn't mess the data up too bad :)
-                    Some(1.0)
-                } else {
-                    None
-                }
-            }
-        };
-        Ok(Self {
-            would_throw,
-            expected_rancidness,
-            desired_rancidness,
-            notes,
-        })
-    }
+use clap::{Parser, Subcommand, ValueEnum};
+use csv::{Writer, WriterBuilder};
+use throw_cruncher::{
+    Error, FlattenedReport, FlattenedResponse, Format, HistogramRow, Imputation, ResponseSource,
+    Survey, Weighting, codebook,
+    corrections::{self, Override},
+    diff::{self, Run},
+    html, keywords, latex, markdown, missing,
+    redact::{self, Identities},
+    schema::{self, Artifact},
+    sentiment,
+    source::{self, DelimitedSource, Sheet},
+    sqlite, svg, terminal,
+    themes::{self, Lexicon},
+    waves::{self, Wave, WaveSpec},
+    workbook::{self, Table},
+};
 
-    fn massage(self) -> Self {
-        Self {
-            would_throw: self.would_throw,
-            expected_rancidness: self.expected_rancidness.map(|f| f.clamp(1.0, 5.0)),
-            desired_rancidness: self.desired_rancidness.map(|f| f.clamp(1.0, 5.0)),
-            notes: self.notes,
-        }
-    }
+#[derive(Parser)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    args: Args,
+    #[command(flatten)]
+    outputs: Outputs,
 }
 
-fn parse_bool(input: &str) -> Result<bool, &'static str> {
-    match input {
-        "Yes" => Ok(true),
-        "No" => Ok(false),
-        _ => Err(format!("malformed bool: {input}").leak()),
-    }
+#[derive(Subcommand)]
+enum Command {
+    /// render a human-readable report instead of writing the usual result files
+    Report {
+        /// the kind of report to render
+        #[arg(long, value_enum)]
+        format: ReportFormat,
+        /// write the report here instead of to stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        /// decimal places for fractional numbers in latex tables
+        #[arg(long, default_value_t = 2)]
+        precision: usize,
+        #[command(flatten)]
+        args: Args,
+    },
+    /// print the json schema of one of the json artifacts
+    Schema {
+        #[arg(value_enum)]
+        artifact: Artifact,
+    },
+    /// check a json artifact against its schema
+    Validate {
+        /// the json file to check
+        file: PathBuf,
+        /// which artifact the file is, if it can't be told from its name
+        #[arg(long, value_enum)]
+        artifact: Option<Artifact>,
+    },
+    /// compare two runs: report outputs, or survey inputs run through the pipeline
+    Diff {
+        /// the earlier run
+        old: PathBuf,
+        /// the later run
+        new: PathBuf,
+        /// print the changes as json
+        #[arg(long)]
+        json: bool,
+        /// weigh survey runs like this before reporting on them, to match the reports they're
+        /// compared against
+        #[command(flatten)]
+        weights: WeightArgs,
+        /// fill in missing rancidness answers of survey runs before reporting on them
+        #[arg(long, value_enum)]
+        impute: Option<Imputation>,
+    },
+    /// merge several runs of the survey and report how each item moved between them
+    Waves {
+        /// every wave as LABEL=PATH, oldest first
+        #[arg(required = true, num_args = 2.., value_name = "LABEL=PATH")]
+        waves: Vec<WaveSpec>,
+    },
 }
 
-enum FloatNote<'n> {
-    Float(f64),
-    FloatNote(f64, &'n str),
+#[derive(Clone, Copy, ValueEnum)]
+enum ReportFormat {
+    /// a self-contained html page with inline charts
+    Html,
+    /// a ranked markdown table and a few notable notes
+    Markdown,
+    /// booktabs latex tables: the summary, the ranking and paired gap tests
+    Latex,
+    /// the per-item summary statistics as json
+    Json,
 }
 
-fn best_effort_parse_float<'n>(input: &'n str) -> Result<FloatNote<'n>, &'n str> {
-    static REGEX: LazyLock<Regex> =
-        LazyLock::new(|| Regex::new(r"([-]?[0-9]*\.?,?[0-9]+)").unwrap());
-
-    if let Ok(result) = input.parse() {
-        Ok(FloatNote::Float(result))
-    } else if let Some(captures) = REGEX.captures(input) {
-        let capture = captures.get(0).unwrap();
-        Ok(FloatNote::FloatNote(
-            capture.as_str().parse().unwrap(),
-            input,
-        ))
-    } else {
-        Err(input)
-    }
+#[derive(clap::Args)]
+struct Args {
+    /// the survey export to crunch
+    #[arg(default_value = "throwcsv.csv")]
+    input: PathBuf,
+    /// the input format, if it can't be told from the file extension
+    #[arg(long, value_enum)]
+    input_format: Option<Format>,
+    /// the field delimiter for delimited input, overriding the format's default
+    #[arg(long)]
+    delimiter: Option<char>,
+    /// the quote character for delimited input, overriding the default of '"'
+    #[arg(long)]
+    quote: Option<char>,
+    /// the sheet to read from spreadsheet input, by name or zero-based index
+    #[arg(long, default_value = "0")]
+    sheet: Sheet,
+    #[command(flatten)]
+    weights: WeightArgs,
+    /// patch hand corrections from this csv over the raw rows, keyed by row or user id
+    #[arg(long)]
+    corrections: Option<PathBuf>,
+    /// fill in missing rancidness answers before reporting
+    #[arg(long, value_enum)]
+    impute: Option<Imputation>,
+    /// scrub profanity and contact details from the free text and hide who answered, logging
+    /// what was scrubbed to result_redactions.csv
+    #[arg(long)]
+    publish: bool,
+    /// what --publish does with the user id and display name
+    #[arg(long, value_enum, default_value_t, requires = "publish")]
+    identities: Identities,
+    /// the secret key identities are hashed with, so hashes line up across runs; a random
+    /// key is used for each run otherwise. Never publish it alongside the outputs
+    #[arg(long, requires = "publish")]
+    identity_key: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
-struct Response {
-    artichoke: Fruit,
-    avocado: Fruit,
-    banana: Fruit,
-    brussels_sprout: Fruit,
-    cantaloupe: Fruit,
-    cauliflower: Fruit,
-    chard: Fruit,
-    crimini_mushroom: Fruit,
-    golden_beet: Fruit,
-    jalapeno: Fruit,
-    kiwi: Fruit,
-    korean_melon: Fruit,
-    lime: Fruit,
-    pear: Fruit,
-    plucot: Fruit,
-    red_grapefruit: Fruit,
-    red_onion: Fruit,
-    straightneck_squash: Fruit,
-    strawberry: Fruit,
-    tomatillo: Fruit,
+// extra outputs for the default run, on top of the usual result files
+#[derive(clap::Args)]
+struct Outputs {
+    /// don't print the summary table
+    #[arg(long, short)]
+    quiet: bool,
+    /// print the summary without colors, which are also left out when stdout isn't a terminal
+    #[arg(long)]
+    no_color: bool,
+    /// also write every artifact to this workbook, as ods or xlsx depending on its extension
+    #[arg(long)]
+    workbook: Option<PathBuf>,
+    /// also export respondents, items and answers to this sqlite database
+    #[arg(long)]
+    sqlite: Option<PathBuf>,
+    /// sort notes into themes with this `theme,pattern` csv instead of the built-in lexicon
+    #[arg(long)]
+    lexicon: Option<PathBuf>,
+    /// how many words and word pairs to keep per item in the keyword counts
+    #[arg(long, default_value_t = 10)]
+    top_terms: usize,
+    /// also write svg charts, overall and per item, into this directory
+    #[arg(long)]
+    charts: Option<PathBuf>,
+    /// also write the long-format answers and the report as parquet and arrow files here
+    #[cfg(feature = "columnar")]
+    #[arg(long)]
+    columnar: Option<PathBuf>,
 }
 
-#[derive(Debug, Serialize)]
-struct FlattenedResponse {
-    artichoke_would_throw: bool,
-    artichoke_expected_rancidness: Option<f64>,
-    artichoke_desired_rancidness: Option<f64>,
-    avocado_would_throw: bool,
-    avocado_expected_rancidness: Option<f64>,
-    avocado_desired_rancidness: Option<f64>,
-    banana_would_throw: bool,
-    banana_expected_rancidness: Option<f64>,
-    banana_desired_rancidness: Option<f64>,
-    brussels_sprout_would_throw: bool,
-    brussels_sprout_expected_rancidness: Option<f64>,
-    brussels_sprout_desired_rancidness: Option<f64>,
-    cantaloupe_would_throw: bool,
-    cantaloupe_expected_rancidness: Option<f64>,
-    cantaloupe_desired_rancidness: Option<f64>,
-    cauliflower_would_throw: bool,
-    cauliflower_expected_rancidness: Option<f64>,
-    cauliflower_desired_rancidness: Option<f64>,
-    chard_would_throw: bool,
-    chard_expected_rancidness: Option<f64>,
-    chard_desired_rancidness: Option<f64>,
-    crimini_mushroom_would_throw: bool,
-    crimini_mushroom_expected_rancidness: Option<f64>,
-    crimini_mushroom_desired_rancidness: Option<f64>,
-    golden_beet_would_throw: bool,
-    golden_beet_expected_rancidness: Option<f64>,
-    golden_beet_desired_rancidness: Option<f64>,
-    jalapeno_would_throw: bool,
-    jalapeno_expected_rancidness: Option<f64>,
-    jalapeno_desired_rancidness: Option<f64>,
-    kiwi_would_throw: bool,
-    kiwi_expected_rancidness: Option<f64>,
-    kiwi_desired_rancidness: Option<f64>,
-    korean_melon_would_throw: bool,
-    korean_melon_expected_rancidness: Option<f64>,
-    korean_melon_desired_rancidness: Option<f64>,
-    lime_would_throw: bool,
-    lime_expected_rancidness: Option<f64>,
-    lime_desired_rancidness: Option<f64>,
-    pear_would_throw: bool,
-    pear_expected_rancidness: Option<f64>,
-    pear_desired_rancidness: Option<f64>,
-    plucot_would_throw: bool,
-    plucot_expected_rancidness: Option<f64>,
-    plucot_desired_rancidness: Option<f64>,
-    red_grapefruit_would_throw: bool,
-    red_grapefruit_expected_rancidness: Option<f64>,
-    red_grapefruit_desired_rancidness: Option<f64>,
-    red_onion_would_throw: bool,
-    red_onion_expected_rancidness: Option<f64>,
-    red_onion_desired_rancidness: Option<f64>,
-    straightneck_squash_would_throw: bool,
-    straightneck_squash_expected_rancidness: Option<f64>,
-    straightneck_squash_desired_rancidness: Option<f64>,
-    strawberry_would_throw: bool,
-    strawberry_expected_rancidness: Option<f64>,
-    strawberry_desired_rancidness: Option<f64>,
-    tomatillo_would_throw: bool,
-    tomatillo_expected_rancidness: Option<f64>,
-    tomatillo_desired_rancidness: Option<f64>,
+#[derive(clap::Args)]
+struct WeightArgs {
+    /// take each respondent's weight from this column of the input
+    #[arg(long, group = "weighting")]
+    weight_column: Option<String>,
+    /// take respondent weights from a csv with "User ID" and "weight" columns, which must
+    /// list every User ID in the survey
+    #[arg(long, group = "weighting")]
+    weight_file: Option<PathBuf>,
+    /// down-weight respondents by the share of their answers that had to be coerced
+    #[arg(long, group = "weighting")]
+    quality_weights: bool,
 }
 
-impl FlattenedResponse {
-    fn map(response: &Response) -> Self {
-        Self {
-            artichoke_would_throw: response.artichoke.would_throw,
-            artichoke_expected_rancidness: response.artichoke.expected_rancidness,
-            artichoke_desired_rancidness: response.artichoke.desired_rancidness,
-            avocado_would_throw: response.avocado.would_throw,
-            avocado_expected_rancidness: response.avocado.expected_rancidness,
-            avocado_desired_rancidness: response.avocado.desired_rancidness,
-            banana_would_throw: response.banana.would_throw,
-            banana_expected_rancidness: response.banana.expected_rancidness,
-            banana_desired_rancidness: response.banana.desired_rancidness,
-            brussels_sprout_would_throw: response.brussels_sprout.would_throw,
-            brussels_sprout_expected_rancidness: response.brussels_sprout.expected_rancidness,
-            brussels_sprout_desired_rancidness: response.brussels_sprout.desired_rancidness,
-            cantaloupe_would_throw: response.cantaloupe.would_throw,
-            cantaloupe_expected_rancidness: response.cantaloupe.expected_rancidness,
-            cantaloupe_desired_rancidness: response.cantaloupe.desired_rancidness,
-            cauliflower_would_throw: response.cauliflower.would_throw,
-            cauliflower_expected_rancidness: response.cauliflower.expected_rancidness,
-            cauliflower_desired_rancidness: response.cauliflower.desired_rancidness,
-            chard_would_throw: response.chard.would_throw,
-            chard_expected_rancidness: response.chard.expected_rancidness,
-            chard_desired_rancidness: response.chard.desired_rancidness,
-            crimini_mushroom_would_throw: response.crimini_mushroom.would_throw,
-            crimini_mushroom_expected_rancidness: response.crimini_mushroom.expected_rancidness,
-            crimini_mushroom_desired_rancidness: response.crimini_mushroom.desired_rancidness,
-            golden_beet_would_throw: response.golden_beet.would_throw,
-            golden_beet_expected_rancidness: response.golden_beet.expected_rancidness,
-            golden_beet_desired_rancidness: response.golden_beet.desired_rancidness,
-            jalapeno_would_throw: response.jalapeno.would_throw,
-            jalapeno_expected_rancidness: response.jalapeno.expected_rancidness,
-            jalapeno_desired_rancidness: response.jalapeno.desired_rancidness,
-            kiwi_would_throw: response.kiwi.would_throw,
-            kiwi_expected_rancidness: response.kiwi.expected_rancidness,
-            kiwi_desired_rancidness: response.kiwi.desired_rancidness,
-            korean_melon_would_throw: response.korean_melon.would_throw,
-            korean_melon_expected_rancidness: response.korean_melon.expected_rancidness,
-            korean_melon_desired_rancidness: response.korean_melon.desired_rancidness,
-            lime_would_throw: response.lime.would_throw,
-            lime_expected_rancidness: response.lime.expected_rancidness,
-            lime_desired_rancidness: response.lime.desired_rancidness,
-            pear_would_throw: response.pear.would_throw,
-            pear_expected_rancidness: response.pear.expected_rancidness,
-            pear_desired_rancidness: response.pear.desired_rancidness,
-            plucot_would_throw: response.plucot.would_throw,
-            plucot_expected_rancidness: response.plucot.expected_rancidness,
-            plucot_desired_rancidness: response.plucot.desired_rancidness,
-            red_grapefruit_would_throw: response.red_grapefruit.would_throw,
-            red_grapefruit_expected_rancidness: response.red_grapefruit.expected_rancidness,
-            red_grapefruit_desired_rancidness: response.red_grapefruit.desired_rancidness,
-            red_onion_would_throw: response.red_onion.would_throw,
-            red_onion_expected_rancidness: response.red_onion.expected_rancidness,
-            red_onion_desired_rancidness: response.red_onion.desired_rancidness,
-            straightneck_squash_would_throw: response.straightneck_squash.would_throw,
-            straightneck_squash_expected_rancidness: response
-                .straightneck_squash
-                .expected_rancidness,
-            straightneck_squash_desired_rancidness: response.straightneck_squash.desired_rancidness,
-            strawberry_would_throw: response.strawberry.would_throw,
-            strawberry_expected_rancidness: response.strawberry.expected_rancidness,
-            strawberry_desired_rancidness: response.strawberry.desired_rancidness,
-            tomatillo_would_throw: response.tomatillo.would_throw,
-            tomatillo_expected_rancidness: response.tomatillo.expected_rancidness,
-            tomatillo_desired_rancidness: response.tomatillo.desired_rancidness,
+impl WeightArgs {
+    fn weighting(&self) -> Weighting {
+        if let Some(column) = &self.weight_column {
+            Weighting::Column(column.clone())
+        } else if let Some(path) = &self.weight_file {
+            Weighting::File(path.clone())
+        } else if self.quality_weights {
+            Weighting::Quality
+        } else {
+            Weighting::Uniform
         }
     }
 }
 
-#[derive(Debug, Serialize)]
-struct FlattenedReport {
-    artichoke_would_throw_count: usize,
-    artichoke_would_not_throw_count: usize,
-    artichoke_average_expected_rancidness: f64,
-    artichoke_average_desired_rancidness: f64,
-    avocado_would_throw_count: usize,
-    avocado_would_not_throw_count: usize,
-    avocado_average_expected_rancidness: f64,
-    avocado_average_desired_rancidness: f64,
-    banana_would_throw_count: usize,
-    banana_would_not_throw_count: usize,
-    banana_average_expected_rancidness: f64,
-    banana_average_desired_rancidness: f64,
-    brussels_sprout_would_throw_count: usize,
-    brussels_sprout_would_not_throw_count: usize,
-    brussels_sprout_average_expected_rancidness: f64,
-    brussels_sprout_average_desired_rancidness: f64,
-    cantaloupe_would_throw_count: usize,
-    cantaloupe_would_not_throw_count: usize,
-    cantaloupe_average_expected_rancidness: f64,
-    cantaloupe_average_desired_rancidness: f64,
-    cauliflower_would_throw_count: usize,
-    cauliflower_would_not_throw_count: usize,
-    cauliflower_average_expected_rancidness: f64,
-    cauliflower_average_desired_rancidness: f64,
-    chard_would_throw_count: usize,
-    chard_would_not_throw_count: usize,
-    chard_average_expected_rancidness: f64,
-    chard_average_desired_rancidness: f64,
-    crimini_mushroom_would_throw_count: usize,
-    crimini_mushroom_would_not_throw_count: usize,
-    crimini_mushroom_average_expected_rancidness: f64,
-    crimini_mushroom_average_desired_rancidness: f64,
-    golden_beet_would_throw_count: usize,
-    golden_beet_would_not_throw_count: usize,
-    golden_beet_average_expected_rancidness: f64,
-    golden_beet_average_desired_rancidness: f64,
-    jalapeno_would_throw_count: usize,
-    jalapeno_would_not_throw_count: usize,
-    jalapeno_average_expected_rancidness: f64,
-    jalapeno_average_desired_rancidness: f64,
-    kiwi_would_throw_count: usize,
-    kiwi_would_not_throw_count: usize,
-    kiwi_average_expected_rancidness: f64,
-    kiwi_average_desired_rancidness: f64,
-    korean_melon_would_throw_count: usize,
-    korean_melon_would_not_throw_count: usize,
-    korean_melon_average_expected_rancidness: f64,
-    korean_melon_average_desired_rancidness: f64,
-    lime_would_throw_count: usize,
-    lime_would_not_throw_count: usize,
-    lime_average_expected_rancidness: f64,
-    lime_average_desired_rancidness: f64,
-    pear_would_throw_count: usize,
-    pear_would_not_throw_count: usize,
-    pear_average_expected_rancidness: f64,
-    pear_average_desired_rancidness: f64,
-    plucot_would_throw_count: usize,
-    plucot_would_not_throw_count: usize,
-    plucot_average_expected_rancidness: f64,
-    plucot_average_desired_rancidness: f64,
-    red_grapefruit_would_throw_count: usize,
-    red_grapefruit_would_not_throw_count: usize,
-    red_grapefruit_average_expected_rancidness: f64,
-    red_grapefruit_average_desired_rancidness: f64,
-    red_onion_would_throw_count: usize,
-    red_onion_would_not_throw_count: usize,
-    red_onion_average_expected_rancidness: f64,
-    red_onion_average_desired_rancidness: f64,
-    straightneck_squash_would_throw_count: usize,
-    straightneck_squash_would_not_throw_count: usize,
-    straightneck_squash_average_expected_rancidness: f64,
-    straightneck_squash_average_desired_rancidness: f64,
-    strawberry_would_throw_count: usize,
-    strawberry_would_not_throw_count: usize,
-    strawberry_average_expected_rancidness: f64,
-    strawberry_average_desired_rancidness: f64,
-    tomatillo_would_throw_count: usize,
-    tomatillo_would_not_throw_count: usize,
-    tomatillo_average_expected_rancidness: f64,
-    tomatillo_average_desired_rancidness: f64,
-}
+impl Args {
+    fn source(&self) -> Result<Box<dyn ResponseSource>, Error> {
+        let format = self
+            .input_format
+            .unwrap_or_else(|| Format::from_path(&self.input));
+        if self.delimiter.is_none() && self.quote.is_none() {
+            return source::open(&self.input, Some(format), &self.sheet);
+        }
+        if !matches!(format, Format::Csv | Format::Tsv) {
+            return Err(Error::Source(
+                "--delimiter and --quote only apply to csv and tsv input",
+            ));
+        }
 
-impl FlattenedReport {
-    fn from_vec_response(vec_response: VecResponse) -> Self {
-        let (
-            artichoke_would_throw_count,
-            artichoke_would_not_throw_count,
-            artichoke_average_expected_rancidness,
-            artichoke_average_desired_rancidness,
-        ) = report(&vec_response.artichoke);
-        let (
-            avocado_would_throw_count,
-            avocado_would_not_throw_count,
-            avocado_average_expected_rancidness,
-            avocado_average_desired_rancidness,
-        ) = report(&vec_response.avocado);
-        let (
-            banana_would_throw_count,
-            banana_would_not_throw_count,
-            banana_average_expected_rancidness,
-            banana_average_desired_rancidness,
-        ) = report(&vec_response.banana);
-        let (
-            brussels_sprout_would_throw_count,
-            brussels_sprout_would_not_throw_count,
-            brussels_sprout_average_expected_rancidness,
-            brussels_sprout_average_desired_rancidness,
-        ) = report(&vec_response.brussels_sprout);
-        let (
-            cantaloupe_would_throw_count,
-            cantaloupe_would_not_throw_count,
-            cantaloupe_average_expected_rancidness,
-            cantaloupe_average_desired_rancidness,
-        ) = report(&vec_response.cantaloupe);
-        let (
-            cauliflower_would_throw_count,
-            cauliflower_would_not_throw_count,
-            cauliflower_average_expected_rancidness,
-            cauliflower_average_desired_rancidness,
-        ) = report(&vec_response.cauliflower);
-        let (
-            chard_would_throw_count,
-            chard_would_not_throw_count,
-            chard_average_expected_rancidness,
-            chard_average_desired_rancidness,
-        ) = report(&vec_response.chard);
-        let (
-            crimini_mushroom_would_throw_count,
-            crimini_mushroom_would_not_throw_count,
-            crimini_mushroom_average_expected_rancidness,
-            crimini_mushroom_average_desired_rancidness,
-        ) = report(&vec_response.crimini_mushroom);
-        let (
-            golden_beet_would_throw_count,
-            golden_beet_would_not_throw_count,
-            golden_beet_average_expected_rancidness,
-            golden_beet_average_desired_rancidness,
-        ) = report(&vec_response.golden_beet);
-        let (
-            jalapeno_would_throw_count,
-            jalapeno_would_not_throw_count,
-            jalapeno_average_expected_rancidness,
-            jalapeno_average_desired_rancidness,
-        ) = report(&vec_response.jalapeno);
-        let (
-            kiwi_would_throw_count,
-            kiwi_would_not_throw_count,
-            kiwi_average_expected_rancidness,
-            kiwi_average_desired_rancidness,
-        ) = report(&vec_response.kiwi);
-        let (
-            korean_melon_would_throw_count,
-            korean_melon_would_not_throw_count,
-            korean_melon_average_expected_rancidness,
-            korean_melon_average_desired_rancidness,
-        ) = report(&vec_response.korean_melon);
-        let (
-            lime_would_throw_count,
-            lime_would_not_throw_count,
-            lime_average_expected_rancidness,
-            lime_average_desired_rancidness,
-        ) = report(&vec_response.lime);
-        let (
-            pear_would_throw_count,
-            pear_would_not_throw_count,
-            pear_average_expected_rancidness,
-            pear_average_desired_rancidness,
-        ) = report(&vec_response.pear);
-        let (
-            plucot_would_throw_count,
-            plucot_would_not_throw_count,
-            plucot_average_expected_rancidness,
-            plucot_average_desired_rancidness,
-        ) = report(&vec_response.plucot);
-        let (
-            red_grapefruit_would_throw_count,
-            red_grapefruit_would_not_throw_count,
-            red_grapefruit_average_expected_rancidness,
-            red_grapefruit_average_desired_rancidness,
-        ) = report(&vec_response.red_grapefruit);
-        let (
-            red_onion_would_throw_count,
-            red_onion_would_not_throw_count,
-            red_onion_average_expected_rancidness,
-            red_onion_average_desired_rancidness,
-        ) = report(&vec_response.red_onion);
-        let (
-            straightneck_squash_would_throw_count,
-            straightneck_squash_would_not_throw_count,
-            straightneck_squash_average_expected_rancidness,
-            straightneck_squash_average_desired_rancidness,
-        ) = report(&vec_response.straightneck_squash);
-        let (
-            strawberry_would_throw_count,
-            strawberry_would_not_throw_count,
-            strawberry_average_expected_rancidness,
-            strawberry_average_desired_rancidness,
-        ) = report(&vec_response.strawberry);
-        let (
-            tomatillo_would_throw_count,
-            tomatillo_would_not_throw_count,
-            tomatillo_average_expected_rancidness,
-            tomatillo_average_desired_rancidness,
-        ) = report(&vec_response.tomatillo);
-
-        Self {
-            artichoke_would_throw_count,
-            artichoke_would_not_throw_count,
-            artichoke_average_expected_rancidness,
-            artichoke_average_desired_rancidness,
-            avocado_would_throw_count,
-            avocado_would_not_throw_count,
-            avocado_average_expected_rancidness,
-            avocado_average_desired_rancidness,
-            banana_would_throw_count,
-            banana_would_not_throw_count,
-            banana_average_expected_rancidness,
-            banana_average_desired_rancidness,
-            brussels_sprout_would_throw_count,
-            brussels_sprout_would_not_throw_count,
-            brussels_sprout_average_expected_rancidness,
-            brussels_sprout_average_desired_rancidness,
-            cantaloupe_would_throw_count,
-            cantaloupe_would_not_throw_count,
-            cantaloupe_average_expected_rancidness,
-            cantaloupe_average_desired_rancidness,
-            cauliflower_would_throw_count,
-            cauliflower_would_not_throw_count,
-            cauliflower_average_expected_rancidness,
-            cauliflower_average_desired_rancidness,
-            chard_would_throw_count,
-            chard_would_not_throw_count,
-            chard_average_expected_rancidness,
-            chard_average_desired_rancidness,
-            crimini_mushroom_would_throw_count,
-            crimini_mushroom_would_not_throw_count,
-            crimini_mushroom_average_expected_rancidness,
-            crimini_mushroom_average_desired_rancidness,
-            golden_beet_would_throw_count,
-            golden_beet_would_not_throw_count,
-            golden_beet_average_expected_rancidness,
-            golden_beet_average_desired_rancidness,
-            jalapeno_would_throw_count,
-            jalapeno_would_not_throw_count,
-            jalapeno_average_expected_rancidness,
-            jalapeno_average_desired_rancidness,
-            kiwi_would_throw_count,
-            kiwi_would_not_throw_count,
-            kiwi_average_expected_rancidness,
-            kiwi_average_desired_rancidness,
-            korean_melon_would_throw_count,
-            korean_melon_would_not_throw_count,
-            korean_melon_average_expected_rancidness,
-            korean_melon_average_desired_rancidness,
-            lime_would_throw_count,
-            lime_would_not_throw_count,
-            lime_average_expected_rancidness,
-            lime_average_desired_rancidness,
-            pear_would_throw_count,
-            pear_would_not_throw_count,
-            pear_average_expected_rancidness,
-            pear_average_desired_rancidness,
-            plucot_would_throw_count,
-            plucot_would_not_throw_count,
-            plucot_average_expected_rancidness,
-            plucot_average_desired_rancidness,
-            red_grapefruit_would_throw_count,
-            red_grapefruit_would_not_throw_count,
-            red_grapefruit_average_expected_rancidness,
-            red_grapefruit_average_desired_rancidness,
-            red_onion_would_throw_count,
-            red_onion_would_not_throw_count,
-            red_onion_average_expected_rancidness,
-            red_onion_average_desired_rancidness,
-            straightneck_squash_would_throw_count,
-            straightneck_squash_would_not_throw_count,
-            straightneck_squash_average_expected_rancidness,
-            straightneck_squash_average_desired_rancidness,
-            strawberry_would_throw_count,
-            strawberry_would_not_throw_count,
-            strawberry_average_expected_rancidness,
-            strawberry_average_desired_rancidness,
-            tomatillo_would_throw_count,
-            tomatillo_would_not_throw_count,
-            tomatillo_average_expected_rancidness,
-            tomatillo_average_desired_rancidness,
+        let delimiter = self
+            .delimiter
+            .unwrap_or(if format == Format::Tsv { '\t' } else { ',' });
+        let quote = self.quote.unwrap_or('"');
+        // the csv reader works in bytes, so anything wider would be silently truncated
+        if !delimiter.is_ascii() || !quote.is_ascii() {
+            return Err(Error::Source(
+                "--delimiter and --quote must be ascii characters",
+            ));
         }
+        Ok(Box::new(DelimitedSource::with_delimiter(
+            File::open(&self.input)?,
+            delimiter as u8,
+            quote as u8,
+        )))
     }
-}
 
-struct VecResponse {
-    artichoke: Vec<Fruit>,
-    avocado: Vec<Fruit>,
-    banana: Vec<Fruit>,
-    brussels_sprout: Vec<Fruit>,
-    cantaloupe: Vec<Fruit>,
-    cauliflower: Vec<Fruit>,
-    chard: Vec<Fruit>,
-    crimini_mushroom: Vec<Fruit>,
-    golden_beet: Vec<Fruit>,
-    jalapeno: Vec<Fruit>,
-    kiwi: Vec<Fruit>,
-    korean_melon: Vec<Fruit>,
-    lime: Vec<Fruit>,
-    pear: Vec<Fruit>,
-    plucot: Vec<Fruit>,
-    red_grapefruit: Vec<Fruit>,
-    red_onion: Vec<Fruit>,
-    straightneck_squash: Vec<Fruit>,
-    strawberry: Vec<Fruit>,
-    tomatillo: Vec<Fruit>,
-}
+    // the hand corrections to patch over the raw rows, which a responses file doesn't have
+    fn overrides(&self, format: Format) -> Result<Vec<Override>, Error> {
+        match &self.corrections {
+            None => Ok(Vec::new()),
+            Some(_) if format == Format::Responses => Err(Error::Correction(
+                "--corrections only applies to raw survey rows, edit the responses file instead"
+                    .to_owned(),
+            )),
+            Some(path) => corrections::read(path).map_err(Error::Correction),
+        }
+    }
 
-impl VecResponse {
-    fn from_iter(iter: impl Iterator<Item = Response>) -> Self {
-        let mut artichoke = Vec::default();
-        let mut avocado = Vec::default();
-        let mut banana = Vec::default();
-        let mut brussels_sprout = Vec::default();
-        let mut cantaloupe = Vec::default();
-        let mut cauliflower = Vec::default();
-        let mut chard = Vec::default();
-        let mut crimini_mushroom = Vec::default();
-        let mut golden_beet = Vec::default();
-        let mut jalapeno = Vec::default();
-        let mut kiwi = Vec::default();
-        let mut korean_melon = Vec::default();
-        let mut lime = Vec::default();
-        let mut pear = Vec::default();
-        let mut plucot = Vec::default();
-        let mut red_grapefruit = Vec::default();
-        let mut red_onion = Vec::default();
-        let mut straightneck_squash = Vec::default();
-        let mut strawberry = Vec::default();
-        let mut tomatillo = Vec::default();
-
-        for response in iter {
-            artichoke.push(response.artichoke);
-            avocado.push(response.avocado);
-            banana.push(response.banana);
-            brussels_sprout.push(response.brussels_sprout);
-            cantaloupe.push(response.cantaloupe);
-            cauliflower.push(response.cauliflower);
-            chard.push(response.chard);
-            crimini_mushroom.push(response.crimini_mushroom);
-            golden_beet.push(response.golden_beet);
-            jalapeno.push(response.jalapeno);
-            kiwi.push(response.kiwi);
-            korean_melon.push(response.korean_melon);
-            lime.push(response.lime);
-            pear.push(response.pear);
-            plucot.push(response.plucot);
-            red_grapefruit.push(response.red_grapefruit);
-            red_onion.push(response.red_onion);
-            straightneck_squash.push(response.straightneck_squash);
-            strawberry.push(response.strawberry);
-            tomatillo.push(response.tomatillo);
+    // the ingested survey and each respondent's weight
+    fn ingest(&self) -> (Survey, Vec<f64>) {
+        let format = self
+            .input_format
+            .unwrap_or_else(|| Format::from_path(&self.input));
+        let overrides = self.overrides(format).expect("couldn't read corrections");
+        let mut survey = if format == Format::Responses {
+            Survey::from_json(File::open(&self.input).expect("couldn't open input"))
+        } else {
+            Survey::from_source_with_overrides(
+                self.source().expect("couldn't open input").as_mut(),
+                &overrides,
+            )
         }
+        .expect("data ingest error");
 
-        Self {
-            artichoke,
-            avocado,
-            banana,
-            brussels_sprout,
-            cantaloupe,
-            cauliflower,
-            chard,
-            crimini_mushroom,
-            golden_beet,
-            jalapeno,
-            kiwi,
-            korean_melon,
-            lime,
-            pear,
-            plucot,
-            red_grapefruit,
-            red_onion,
-            straightneck_squash,
-            strawberry,
-            tomatillo,
+        // rows that don't parse are set aside rather than failing the run, so say so
+        if !survey.rejects.is_empty() {
+            eprintln!(
+                "set aside {} row(s) that couldn't be parsed",
+                survey.rejects.len()
+            );
         }
+        if survey.responses.is_empty() {
+            eprintln!("no usable responses in {}", self.input.display());
+            process::exit(1);
+        }
+
+        let weights = survey
+            .weights(&self.weights.weighting())
+            .expect("weighting error");
+        if self.publish {
+            let key = self
+                .identity_key
+                .as_ref()
+                .map_or_else(redact::generate_key, |key| key.as_bytes().to_vec());
+            survey.redact(self.identities, &key);
+        }
+        (survey, weights)
     }
 }
 
-impl Response {
-    fn from_iter<'r>(iter: &mut impl Iterator<Item = &'r str>) -> Result<Self, &'static str> {
-        Ok(Self {
-            artichoke: Fruit::from_iter(iter)?,
-            avocado: Fruit::from_iter(iter)?,
-            banana: Fruit::from_iter(iter)?,
-            brussels_sprout: Fruit::from_iter(iter)?,
-            cantaloupe: Fruit::from_iter(iter)?,
-            cauliflower: Fruit::from_iter(iter)?,
-            chard: Fruit::from_iter(iter)?,
-            crimini_mushroom: Fruit::from_iter(iter)?,
-            golden_beet: Fruit::from_iter(iter)?,
-            jalapeno: Fruit::from_iter(iter)?,
-            kiwi: Fruit::from_iter(iter)?,
-            korean_melon: Fruit::from_iter(iter)?,
-            lime: Fruit::from_iter(iter)?,
-            pear: Fruit::from_iter(iter)?,
-            plucot: Fruit::from_iter(iter)?,
-            red_grapefruit: Fruit::from_iter(iter)?,
-            red_onion: Fruit::from_iter(iter)?,
-            straightneck_squash: Fruit::from_iter(iter)?,
-            strawberry: Fruit::from_iter(iter)?,
-            tomatillo: Fruit::from_iter(iter)?,
-        })
+fn main() {
+    let cli = Cli::parse();
+
+    match cli.command {
+        None => crunch(&cli.args, &cli.outputs),
+        Some(Command::Report {
+            format,
+            output,
+            precision,
+            args,
+        }) => report(&args, format, output.as_deref(), precision),
+        Some(Command::Schema { artifact }) => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&artifact.schema()).unwrap()
+            );
+        }
+        Some(Command::Validate { file, artifact }) => validate(&file, artifact),
+        Some(Command::Diff {
+            old,
+            new,
+            json,
+            weights,
+            impute,
+        }) => {
+            let options = diff::Options {
+                weighting: weights.weighting(),
+                imputation: impute,
+            };
+            compare(&old, &new, &options, json);
+        }
+        Some(Command::Waves { waves }) => merge_waves(&waves),
     }
+}
+
+fn report(args: &Args, format: ReportFormat, output: Option<&Path>, precision: usize) {
+    let (survey, weights) = args.ingest();
+    let mut survey = survey.massage();
+    if let Some(imputation) = args.impute {
+        survey.impute(imputation);
+    }
+
+    let rejects = std::mem::take(&mut survey.rejects);
+    let vec_response = survey.into_vec_response();
+    let missing = missing::table(&vec_response);
+    let reports = vec_response.report(&weights);
 
-    fn massage(self) -> Self {
-        Self {
-            artichoke: self.artichoke.massage(),
-            avocado: self.avocado.massage(),
-            banana: self.banana.massage(),
-            brussels_sprout: self.brussels_sprout.massage(),
-            cantaloupe: self.cantaloupe.massage(),
-            cauliflower: self.cauliflower.massage(),
-            chard: self.chard.massage(),
-            crimini_mushroom: self.crimini_mushroom.massage(),
-            golden_beet: self.golden_beet.massage(),
-            jalapeno: self.jalapeno.massage(),
-            kiwi: self.kiwi.massage(),
-            korean_melon: self.korean_melon.massage(),
-            lime: self.lime.massage(),
-            pear: self.pear.massage(),
-            plucot: self.plucot.massage(),
-            red_grapefruit: self.red_grapefruit.massage(),
-            red_onion: self.red_onion.massage(),
-            straightneck_squash: self.straightneck_squash.massage(),
-            strawberry: self.strawberry.massage(),
-            tomatillo: self.tomatillo.massage(),
+    let rendered = match format {
+        ReportFormat::Html => html::render(&vec_response, &reports, &missing, &rejects),
+        ReportFormat::Markdown => markdown::render(&vec_response, &reports),
+        ReportFormat::Latex => {
+            latex::render(&reports, &vec_response.gap_tests(&weights), precision)
         }
+        ReportFormat::Json => serde_json::to_string_pretty(&reports).unwrap() + "\n",
+    };
+    match output {
+        Some(path) => fs::write(path, rendered).expect("couldn't write report"),
+        None => print!("{rendered}"),
     }
 }
 
-fn main() {
-    let mut reader = Reader::from_path("throwcsv.csv").unwrap();
+fn validate(file: &Path, artifact: Option<Artifact>) {
+    let artifact = artifact
+        .or_else(|| Artifact::from_path(file))
+        .expect("can't tell which artifact this is from its name, pass --artifact");
+    let instance = serde_json::from_reader(File::open(file).expect("couldn't open file"))
+        .expect("couldn't read file as json");
 
-    let responses = reader
-        .records()
-        .map(Result::unwrap)
-        .map(|r| Response::from_iter(&mut r.iter().skip(3)))
-        .collect::<Result<Vec<Response>, _>>()
-        .expect("data ingest error");
+    let violations = schema::validate(artifact, &instance).expect("couldn't compile schema");
+    if violations.is_empty() {
+        println!(
+            "{} is a valid {} artifact",
+            file.display(),
+            artifact.file_name()
+        );
+        return;
+    }
+    for violation in &violations {
+        eprintln!("{violation}");
+    }
+    eprintln!("{} violations", violations.len());
+    process::exit(1);
+}
 
-    serde_json::to_writer_pretty(File::create("result_ingested.json").unwrap(), &responses)
-        .unwrap();
+// prints what changed between two runs
+fn compare(old: &Path, new: &Path, options: &diff::Options, json: bool) {
+    let read = |path: &Path| {
+        Run::from_path(path).unwrap_or_else(|e| panic!("couldn't read {}: {e}", path.display()))
+    };
+    let diff = diff::diff(&read(old), &read(new), options).expect("couldn't compare runs");
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diff).unwrap());
+    } else {
+        print!("{}", diff::render(&diff));
+    }
+}
 
-    let massaged_responses = responses
-        .into_iter()
-        .map(Response::massage)
+// writes the combined answers of every wave and the per-item trends across them
+fn merge_waves(specs: &[WaveSpec]) {
+    let waves = specs
+        .iter()
+        .map(|spec| {
+            Wave::from_path(&spec.label, &spec.path)
+                .unwrap_or_else(|e| panic!("couldn't read wave {}: {e}", spec.label))
+        })
         .collect::<Vec<_>>();
 
+    let mut writer = Writer::from_path("result_waves.csv").unwrap();
+    waves
+        .iter()
+        .flat_map(|w| &w.answers)
+        .for_each(|a| writer.serialize(a).unwrap());
+    let mut writer = Writer::from_path("result_trends.csv").unwrap();
+    waves::trends(&waves)
+        .iter()
+        .for_each(|t| writer.serialize(t).unwrap());
+
+    for wave in &waves {
+        println!(
+            "{}: {} respondents, {} items, {} rejected rows",
+            wave.label,
+            wave.answers
+                .iter()
+                .map(|a| a.respondent + 1)
+                .max()
+                .unwrap_or(0),
+            wave.items.len(),
+            wave.rejects.len()
+        );
+    }
+}
+
+// writes every result file, plus whichever extra outputs were asked for
+fn crunch(args: &Args, outputs: &Outputs) {
+    let (survey, weights) = args.ingest();
+
+    serde_json::to_writer_pretty(
+        File::create("result_ingested.json").unwrap(),
+        &survey.responses,
+    )
+    .unwrap();
+
+    // rejected rows are often short, so the writer can't insist on a fixed width
+    let mut writer = WriterBuilder::new()
+        .flexible(true)
+        .from_path("result_rejects.csv")
+        .unwrap();
+    writer
+        .write_record(["line", "reason"].into_iter().chain(&survey.headers))
+        .unwrap();
+    for reject in &survey.rejects {
+        let line = reject.line.to_string();
+        writer
+            .write_record(
+                [line.as_str(), reject.reason]
+                    .into_iter()
+                    .chain(&reject.record),
+            )
+            .unwrap();
+    }
+
+    if args.publish {
+        let mut writer = Writer::from_path("result_redactions.csv").unwrap();
+        survey
+            .redactions
+            .iter()
+            .for_each(|r| writer.serialize(r).unwrap());
+    }
+
+    if args.corrections.is_some() {
+        let mut writer = Writer::from_path("result_corrections.csv").unwrap();
+        survey
+            .corrections
+            .iter()
+            .for_each(|c| writer.serialize(c).unwrap());
+    }
+
+    let mut tables = vec![
+        Table::from_rows(
+            "ingested",
+            survey.responses.iter().map(FlattenedResponse::map),
+        )
+        .unwrap(),
+    ];
+
+    let ingested = outputs.sqlite.is_some().then(|| survey.responses.clone());
+    let mut survey = survey.massage();
+
+    if let Some(imputation) = args.impute {
+        survey.impute(imputation);
+    }
+
     serde_json::to_writer_pretty(
         File::create("result_massaged.json").unwrap(),
-        &massaged_responses,
+        &survey.responses,
     )
     .unwrap();
 
+    let codebook = codebook::columns(&survey.headers, args.impute);
+    serde_json::to_writer_pretty(File::create("result_codebook.json").unwrap(), &codebook).unwrap();
+    fs::write("result_codebook.md", codebook::markdown(&codebook)).unwrap();
+
     let mut writer = Writer::from_path("result_massaged.csv").unwrap();
-    massaged_responses
+    survey
+        .responses
         .iter()
         .map(FlattenedResponse::map)
         .for_each(|r| writer.serialize(r).unwrap());
 
-    let flattened_report =
-        FlattenedReport::from_vec_response(VecResponse::from_iter(massaged_responses.into_iter()));
+    tables.push(
+        Table::from_rows(
+            "massaged",
+            survey.responses.iter().map(FlattenedResponse::map),
+        )
+        .unwrap(),
+    );
+    let rejects = Table::rejects(&survey);
+
+    if let (Some(path), Some(ingested)) = (&outputs.sqlite, &ingested) {
+        sqlite::export(path, &survey, ingested, &weights).expect("couldn't export to sqlite");
+    }
+
+    #[cfg(feature = "columnar")]
+    let columnar_answers = outputs
+        .columnar
+        .as_ref()
+        .map(|_| throw_cruncher::columnar::answers(&survey, &weights).unwrap());
+
+    let rejected = std::mem::take(&mut survey.rejects);
+    let vec_response = survey.into_vec_response();
+
+    let missing = missing::table(&vec_response);
+    let mut writer = Writer::from_path("result_missing.csv").unwrap();
+    missing.iter().for_each(|r| writer.serialize(r).unwrap());
+
+    let lexicon = outputs
+        .lexicon
+        .as_ref()
+        .map_or_else(Lexicon::default, |path| {
+            Lexicon::from_path(path).expect("couldn't read lexicon")
+        });
+    let mut writer = Writer::from_path("result_notes.csv").unwrap();
+    themes::notes(&vec_response, &lexicon)
+        .iter()
+        .for_each(|r| writer.serialize(r).unwrap());
+    let mut writer = Writer::from_path("result_themes.csv").unwrap();
+    themes::table(&vec_response, &lexicon)
+        .iter()
+        .for_each(|r| writer.serialize(r).unwrap());
+
+    let terms = keywords::table(&vec_response, outputs.top_terms);
+    let mut writer = Writer::from_path("result_keywords.csv").unwrap();
+    terms.iter().for_each(|t| writer.serialize(t).unwrap());
+    serde_json::to_writer_pretty(File::create("result_keywords.json").unwrap(), &terms).unwrap();
+
+    let mut writer = Writer::from_path("result_sentiment.csv").unwrap();
+    sentiment::notes(&vec_response)
+        .iter()
+        .for_each(|r| writer.serialize(r).unwrap());
+    let mut writer = Writer::from_path("result_sentiment_summary.csv").unwrap();
+    vec_response
+        .sentiment(&weights)
+        .iter()
+        .for_each(|r| writer.serialize(r).unwrap());
+
+    let reports = vec_response.report(&weights);
 
     Writer::from_path("result.csv")
         .unwrap()
-        .serialize(flattened_report)
+        .serialize(FlattenedReport::map(&reports))
         .unwrap();
-}
 
-fn report(fruits: &[Fruit]) -> (usize, usize, f64, f64) {
-    (
-        fruits
-            .iter()
-            .filter_map(|f| f.would_throw.then_some(()))
-            .count(),
-        fruits
-            .iter()
-            .filter_map(|f| f.would_throw.not().then_some(()))
-            .count(),
-        fruits
-            .iter()
-            .filter_map(|f| f.expected_rancidness)
-            .zip(1..)
-            .fold(0.0, |s, (e, i)| (e as f64 + s * (i - 1) as f64) / i as f64),
-        fruits
-            .iter()
-            .filter_map(|f| f.desired_rancidness)
-            .zip(1..)
-            .fold(0.0, |s, (e, i)| (e as f64 + s * (i - 1) as f64) / i as f64),
+    let mut writer = Writer::from_path("result_summary.csv").unwrap();
+    reports.iter().for_each(|r| writer.serialize(r).unwrap());
+    serde_json::to_writer_pretty(
+        File::create(Artifact::Report.file_name()).unwrap(),
+        &reports,
+    )
+    .unwrap();
+
+    fs::write(
+        "result_summary.md",
+        markdown::render(&vec_response, &reports),
     )
+    .unwrap();
+
+    let mut writer = Writer::from_path("result_histograms.csv").unwrap();
+    reports
+        .iter()
+        .flat_map(HistogramRow::from_report)
+        .for_each(|r| writer.serialize(r).unwrap());
+
+    if let Some(directory) = &outputs.charts {
+        svg::write(directory, &reports).expect("couldn't write charts");
+        let overall = terms
+            .iter()
+            .filter(|t| t.item == "overall")
+            .cloned()
+            .collect::<Vec<_>>();
+        fs::write(
+            directory.join("words.svg"),
+            svg::word_cloud(&overall, "Most common words in the notes"),
+        )
+        .expect("couldn't write charts");
+    }
+
+    if let Some(path) = &outputs.workbook {
+        tables.extend([
+            Table::from_rows("summary", &reports).unwrap(),
+            Table::from_rows(
+                "histograms",
+                reports.iter().flat_map(HistogramRow::from_report),
+            )
+            .unwrap(),
+            Table::from_rows("missing", &missing).unwrap(),
+            rejects,
+        ]);
+        workbook::write(path, &tables).expect("couldn't write workbook");
+    }
+
+    #[cfg(feature = "columnar")]
+    if let (Some(directory), Some(answers)) = (&outputs.columnar, columnar_answers) {
+        let report = throw_cruncher::columnar::report(&reports).unwrap();
+        throw_cruncher::columnar::write(directory, &[("answers", answers), ("report", report)])
+            .expect("couldn't write columnar output");
+    }
+
+    if !outputs.quiet {
+        let color =
+            !outputs.no_color && env::var_os("NO_COLOR").is_none() && io::stdout().is_terminal();
+        print!(
+            "{}",
+            terminal::render(&vec_response, &reports, &missing, &rejected, color)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corrections_are_rejected_for_responses_input() {
+        let cli = Cli::try_parse_from([
+            "throw-cruncher",
+            "result_massaged.json",
+            "--corrections",
+            "corrections.csv",
+        ])
+        .unwrap();
+
+        assert!(matches!(
+            cli.args.overrides(Format::Responses),
+            Err(Error::Correction(_))
+        ));
+        // only read once it's known to apply, so a missing file is the error here
+        assert!(cli.args.overrides(Format::Csv).is_err());
+        assert!(
+            Cli::try_parse_from(["throw-cruncher", "result_massaged.json"])
+                .unwrap()
+                .args
+                .overrides(Format::Responses)
+                .unwrap()
+                .is_empty()
+        );
+    }
 }
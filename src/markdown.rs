@@ -0,0 +1,139 @@
+//! Renders a short markdown summary, for pasting straight into chat or a wiki.
+
+use std::fmt::Write as _;
+
+use crate::{Fruit, ItemReport, VecResponse, display_name, ranking};
+
+/// How many notes to quote per item in the notable notes section.
+const NOTES_PER_ITEM: usize = 2;
+/// Notes with fewer words than this are usually "n/a" or a bare number, not worth quoting.
+const NOTABLE_WORDS: usize = 4;
+/// Longer notes are cut short with an ellipsis.
+const NOTE_LENGTH: usize = 140;
+
+/// Renders the items as a table ranked by throw share, followed by a few of the more
+/// substantial notes left on each item.
+///
+/// `reports` is the [`VecResponse::report`] of `vec_response`.
+pub fn render(vec_response: &VecResponse, reports: &[ItemReport; 20]) -> String {
+    let mut markdown = format!(
+        "# Throw survey results\n\n{} responses. Rancidness is on a scale of 1 to 5.\n\n",
+        vec_response.len()
+    );
+
+    markdown.push_str("| # | Item | Throw % | Expected (mean) | Expected (median) | Desired (mean) | Desired (median) | Effective n |\n");
+    markdown.push_str("|--:|:-----|--------:|----------------:|------------------:|---------------:|-----------------:|------------:|\n");
+    let ranked = ranking(reports);
+    for (rank, report) in (1..).zip(&ranked) {
+        let _ = writeln!(
+            markdown,
+            "| {rank} | {} | {:.0}% | {:.2} | {:.2} | {:.2} | {:.2} | {:.0} |",
+            display_name(report.item),
+            report.throw_share() * 100.0,
+            report.average_expected_rancidness,
+            report.median_expected_rancidness,
+            report.average_desired_rancidness,
+            report.median_desired_rancidness,
+            report.effective_sample_size
+        );
+    }
+
+    let items = vec_response.items();
+    let mut notable = String::new();
+    for report in &ranked {
+        let Some((_, fruits)) = items.iter().find(|(item, _)| *item == report.item) else {
+            continue;
+        };
+        for note in notable_notes(fruits) {
+            let _ = writeln!(
+                notable,
+                "- **{}**: {}",
+                display_name(report.item),
+                escape(&truncate(note))
+            );
+        }
+    }
+    if !notable.is_empty() {
+        markdown.push_str("\n## Notable notes\n\n");
+        markdown.push_str(&notable);
+    }
+
+    markdown
+}
+
+// the few longest notes on an item with something to say, each only once
+fn notable_notes(fruits: &[Fruit]) -> Vec<&str> {
+    let mut notes = fruits
+        .iter()
+        .map(|f| f.notes.trim())
+        .filter(|n| n.split_whitespace().count() >= NOTABLE_WORDS)
+        .collect::<Vec<_>>();
+    // the same note only once, wherever its copies were
+    notes.sort_unstable();
+    notes.dedup();
+    // longest first, so the quotes have something to say
+    notes.sort_by_key(|n| std::cmp::Reverse(n.chars().count()));
+    notes.truncate(NOTES_PER_ITEM);
+    notes
+}
+
+// onto one line, and cut short if need be
+fn truncate(note: &str) -> String {
+    let note = note.split_whitespace().collect::<Vec<_>>().join(" ");
+    if note.chars().count() <= NOTE_LENGTH {
+        return note;
+    }
+    let mut short = note.chars().take(NOTE_LENGTH).collect::<String>();
+    short.truncate(short.trim_end().len());
+    short.push('…');
+    short
+}
+
+// respondents write whatever they like, which shouldn't turn into formatting
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fruit(notes: &str) -> Fruit {
+        Fruit {
+            would_throw: true,
+            expected_rancidness: None,
+            desired_rancidness: None,
+            notes: notes.to_owned(),
+            expected_missing: None,
+            desired_missing: None,
+            imputation: None,
+            corrected: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn notable_notes_are_quoted_once() {
+        let fruits = [
+            fruit("would never throw this"),
+            // as long as the note either side of it, so the copies don't end up adjacent
+            fruit("it is so very tasty!!!"),
+            fruit("would never throw this"),
+            fruit("n/a"),
+        ];
+
+        assert_eq!(
+            notable_notes(&fruits),
+            ["it is so very tasty!!!", "would never throw this"]
+        );
+    }
+}
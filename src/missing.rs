@@ -0,0 +1,294 @@
+//! Why answers are missing, and how to fill them in.
+
+use std::sync::LazyLock;
+
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{Question, Response, VecResponse, themes::Lexicon};
+
+/// Why a rancidness answer couldn't be read as a number.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Missing {
+    /// Nothing was written.
+    Blank,
+    /// Prose was written instead of a number.
+    Text,
+    /// Something was written, but it was neither a number nor prose.
+    Unparseable,
+    /// Prose refusing to throw the item at all, like "i would never throw a brussel sprout".
+    Refused,
+}
+
+impl Missing {
+    /// Works out why `input` (which didn't parse) is missing.
+    pub fn classify(input: &str) -> Self {
+        if input.trim().is_empty() {
+            Self::Blank
+        } else if is_refusal(input) {
+            Self::Refused
+        } else if input.chars().any(char::is_alphabetic) {
+            Self::Text
+        } else {
+            Self::Unparseable
+        }
+    }
+}
+
+/// Whether `text` says the respondent wouldn't throw the item at all, rather than answering
+/// how rancid it would be: whether it falls under the `refusal` theme of the
+/// [`DEFAULT_LEXICON`](crate::themes::DEFAULT_LEXICON), so answers and notes are told apart the
+/// same way. The text is matched with its spelling normalized.
+pub fn is_refusal(text: &str) -> bool {
+    static LEXICON: LazyLock<Lexicon> = LazyLock::new(Lexicon::default);
+
+    LEXICON.classify(text).contains(&"refusal")
+}
+
+/// How to fill in a missing rancidness answer.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Imputation {
+    /// the median answer everyone else gave for the same item and question
+    ItemMedian,
+    /// the respondent's own mean answer to the same question across all items
+    RespondentMean,
+    /// the respondent's answer to the other rancidness question for the same item
+    CarryOver,
+}
+
+/// How often one question about one item went unanswered, and why.
+#[derive(Debug, Serialize)]
+pub struct MissingRow {
+    pub item: &'static str,
+    pub question: Question,
+    pub answered: usize,
+    pub blank: usize,
+    pub text: usize,
+    pub unparseable: usize,
+    /// Refusals are counted apart from the missing answers, and left out of `missing_share`.
+    pub refused: usize,
+    pub missing_share: f64,
+    pub imputed: usize,
+}
+
+/// Tallies missing answers for every item and question.
+pub fn table(vec_response: &VecResponse) -> Vec<MissingRow> {
+    vec_response
+        .items()
+        .into_iter()
+        .flat_map(|(item, fruits)| {
+            Question::ALL.map(|question| {
+                let count = |reason: fn(&Missing) -> bool| {
+                    fruits
+                        .iter()
+                        .filter(|f| f.missing(question).as_ref().is_some_and(reason))
+                        .count()
+                };
+                let blank = count(|m| matches!(m, Missing::Blank));
+                let text = count(|m| matches!(m, Missing::Text));
+                let unparseable = count(|m| matches!(m, Missing::Unparseable));
+                let refused = count(|m| matches!(m, Missing::Refused));
+
+                MissingRow {
+                    item,
+                    question,
+                    // imputed answers are still missing as far as the respondent is concerned
+                    answered: fruits.len() - blank - text - unparseable - refused,
+                    blank,
+                    text,
+                    unparseable,
+                    refused,
+                    missing_share: if fruits.is_empty() {
+                        0.0
+                    } else {
+                        (blank + text + unparseable) as f64 / fruits.len() as f64
+                    },
+                    imputed: fruits
+                        .iter()
+                        .filter(|f| {
+                            f.missing(question).is_some() && f.rancidness(question).is_some()
+                        })
+                        .count(),
+                }
+            })
+        })
+        .collect()
+}
+
+/// Fills in every missing rancidness answer using `imputation`, labeling the filled in
+/// [`Fruit`](crate::Fruit)s so they can be told apart. Refusals are left missing: there's no
+/// rancidness to guess for an item the respondent wouldn't throw at all.
+pub fn impute(responses: &mut [Response], imputation: Imputation) {
+    match imputation {
+        Imputation::ItemMedian => {
+            for question in Question::ALL {
+                let medians: [Option<f64>; 20] = std::array::from_fn(|i| {
+                    median(
+                        responses
+                            .iter()
+                            .filter_map(|r| r.fruits()[i].rancidness(question))
+                            .collect(),
+                    )
+                });
+
+                for response in responses.iter_mut() {
+                    for (fruit, median) in response.fruits_mut().into_iter().zip(medians) {
+                        if fruit.refused_on(question) {
+                            continue;
+                        }
+                        let value = fruit.rancidness_mut(question);
+                        if value.is_none() && median.is_some() {
+                            *value = median;
+                            fruit.imputation = Some(imputation);
+                        }
+                    }
+                }
+            }
+        }
+        Imputation::RespondentMean => {
+            for response in responses.iter_mut() {
+                for question in Question::ALL {
+                    let answers = response
+                        .fruits()
+                        .into_iter()
+                        .filter_map(|f| f.rancidness(question))
+                        .collect::<Vec<_>>();
+                    if answers.is_empty() {
+                        continue;
+                    }
+                    let mean = answers.iter().sum::<f64>() / answers.len() as f64;
+
+                    for fruit in response.fruits_mut() {
+                        if fruit.refused_on(question) {
+                            continue;
+                        }
+                        let value = fruit.rancidness_mut(question);
+                        if value.is_none() {
+                            *value = Some(mean);
+                            fruit.imputation = Some(imputation);
+                        }
+                    }
+                }
+            }
+        }
+        Imputation::CarryOver => {
+            for fruit in responses.iter_mut().flat_map(Response::fruits_mut) {
+                match (fruit.expected_rancidness, fruit.desired_rancidness) {
+                    (Some(expected), None) if !fruit.refused_on(Question::Desired) => {
+                        fruit.desired_rancidness = Some(expected);
+                    }
+                    (None, Some(desired)) if !fruit.refused_on(Question::Expected) => {
+                        fruit.expected_rancidness = Some(desired);
+                    }
+                    _ => continue,
+                }
+                fruit.imputation = Some(imputation);
+            }
+        }
+    }
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    values.sort_by(f64::total_cmp);
+
+    let middle = values.len() / 2;
+    match values.len() {
+        0 => None,
+        n if n % 2 == 0 => Some((values[middle - 1] + values[middle]) / 2.0),
+        _ => Some(values[middle]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_missing_answers() {
+        assert!(matches!(Missing::classify("  "), Missing::Blank));
+        assert!(matches!(Missing::classify("pretty gross"), Missing::Text));
+        assert!(matches!(Missing::classify("??"), Missing::Unparseable));
+        assert!(matches!(
+            Missing::classify("I woult never throw a brussel sprout"),
+            Missing::Refused
+        ));
+    }
+
+    #[test]
+    fn recognizes_refusals() {
+        assert!(is_refusal("i would never throw it"));
+        assert!(is_refusal("please don't"));
+        assert!(is_refusal("I can't throw food"));
+        assert!(!is_refusal("never had one"));
+        assert!(!is_refusal("throw it"));
+        assert!(is_refusal("what a waste"));
+        assert!(is_refusal("do not the kiwi"));
+    }
+
+    #[test]
+    fn refusals_match_the_refusal_theme() {
+        let lexicon = Lexicon::default();
+        for text in [
+            "i would never throw it",
+            "please don't",
+            "wouldnt throw",
+            "never had one",
+            "cruel",
+            "4",
+        ] {
+            assert_eq!(
+                is_refusal(text),
+                lexicon.classify(text).contains(&"refusal"),
+                "{text}"
+            );
+        }
+    }
+
+    // responses answering the first item with `cells`, and every other item with a 3
+    fn responses(cells: &[[&str; 3]]) -> Vec<Response> {
+        cells
+            .iter()
+            .map(|cells| {
+                let row = cells
+                    .iter()
+                    .copied()
+                    .chain((1..20).flat_map(|_| ["Yes", "3", "3"]))
+                    .collect::<Vec<_>>();
+                Response::from_iter(&mut row.into_iter()).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn imputation_leaves_refusals_missing() {
+        for imputation in [
+            Imputation::ItemMedian,
+            Imputation::RespondentMean,
+            Imputation::CarryOver,
+        ] {
+            let mut responses = responses(&[
+                ["Yes", "2", "I would never throw this"],
+                ["No", "4", ""],
+                ["Yes", "3", "5"],
+            ]);
+            impute(&mut responses, imputation);
+
+            let refused = responses[0].fruits()[0];
+            assert_eq!(refused.desired_rancidness, None, "{imputation:?}");
+            assert!(refused.refused_on(Question::Desired));
+            let blank = responses[1].fruits()[0];
+            assert!(blank.desired_rancidness.is_some(), "{imputation:?}");
+            assert!(blank.is_imputed(Question::Desired));
+        }
+    }
+
+    #[test]
+    fn medians() {
+        assert_eq!(median(vec![]), None);
+        assert_eq!(median(vec![3.0, 1.0, 2.0]), Some(2.0));
+        assert_eq!(median(vec![4.0, 1.0, 2.0, 3.0]), Some(2.5));
+    }
+}
@@ -0,0 +1,292 @@
+//! Spelling normalization for free-text answers, so that "woult never throw" or "borinnggg"
+//! still hit the keyword rules written for "would never throw" and "boring".
+
+use std::sync::LazyLock;
+
+use strsim::damerau_levenshtein;
+
+use crate::ITEMS;
+
+/// Words that come up when people answer the survey in prose, on top of the words of every
+/// item name. Misspellings are corrected towards these.
+pub const VOCABULARY: &[&str] = &[
+    // the scale
+    "fresh",
+    "freshly",
+    "ripe",
+    "unripe",
+    "rotten",
+    "rancid",
+    "moldy",
+    "mouldy",
+    "spoiled",
+    "stale",
+    "bruised",
+    "frozen",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "scale",
+    // throwing, and refusing to
+    "throw",
+    "throwing",
+    "thrown",
+    "would",
+    "wouldn't",
+    "never",
+    "please",
+    "don't",
+    "doesn't",
+    "matter",
+    "waste",
+    // everything else people tend to mention
+    "boring",
+    "bruschetta",
+    "delicious",
+    "disgusting",
+    "poisoned",
+    "spicy",
+    "sour",
+    "bitter",
+    "sweet",
+    "surprised",
+    "purposes",
+    "taste",
+    "tastes",
+    "actually",
+    "either",
+];
+
+/// Real words a typo away from the [`VOCABULARY`] or an item name, which are never corrected
+/// since they're much more likely to be meant than a misspelling, like "flesh" or "there".
+pub const ENGLISH: &[&str] = &[
+    "avocados",
+    "bandana",
+    "bananas",
+    "baste",
+    "batter",
+    "better",
+    "bitten",
+    "bitters",
+    "boning",
+    "bowing",
+    "boxing",
+    "bring",
+    "bruiser",
+    "bruises",
+    "butter",
+    "cantaloupes",
+    "caste",
+    "castes",
+    "chair",
+    "charm",
+    "chars",
+    "chart",
+    "chord",
+    "coring",
+    "could",
+    "couldn't",
+    "cruised",
+    "disgustingly",
+    "ether",
+    "felon",
+    "fever",
+    "fitter",
+    "fleshly",
+    "flesh",
+    "gotten",
+    "grapefruits",
+    "haste",
+    "hatter",
+    "hitter",
+    "korea",
+    "koreans",
+    "latter",
+    "lever",
+    "litter",
+    "master",
+    "matted",
+    "matters",
+    "melons",
+    "meson",
+    "moody",
+    "mould",
+    "moulds",
+    "mushrooms",
+    "mutter",
+    "neither",
+    "newer",
+    "onions",
+    "paste",
+    "pastes",
+    "pleas",
+    "pleased",
+    "poisoner",
+    "poring",
+    "rotted",
+    "rotter",
+    "scald",
+    "scales",
+    "scalp",
+    "scaly",
+    "scare",
+    "sever",
+    "shale",
+    "sheet",
+    "sitter",
+    "spice",
+    "spiky",
+    "spoiler",
+    "spout",
+    "sprouts",
+    "squish",
+    "stage",
+    "stake",
+    "stale",
+    "stall",
+    "stalk",
+    "stare",
+    "state",
+    "stile",
+    "stole",
+    "sweat",
+    "sweep",
+    "sweets",
+    "swept",
+    "tasted",
+    "taster",
+    "tasters",
+    "tasty",
+    "there",
+    "threw",
+    "throws",
+    "titter",
+    "tomatillos",
+    "tweet",
+    "union",
+    "wasted",
+    "wastes",
+    "wither",
+    "won't",
+    "world",
+    "wound",
+];
+
+// words shorter than this are too easily another real word to correct, or correct towards
+const MIN_LENGTH: usize = 5;
+// how many letters of a vocabulary word each allowed typo takes, so one for most words and two
+// for long ones like "bruschetta"
+const LETTERS_PER_TYPO: usize = 5;
+
+static WORDS: LazyLock<Vec<String>> = LazyLock::new(|| {
+    VOCABULARY
+        .iter()
+        .map(|w| (*w).to_owned())
+        .chain(
+            ITEMS
+                .iter()
+                .flat_map(|item| item.split('_').map(str::to_owned)),
+        )
+        .collect()
+});
+
+/// Lowercases `text`, squashes letters repeated three or more times down to one, and
+/// corrects words a typo away from the [`VOCABULARY`] or an item name, unless they're
+/// [`ENGLISH`] words already. Everything that isn't a word (numbers, punctuation, spacing) is
+/// kept as it was.
+pub fn normalize(text: &str) -> String {
+    let lowered = collapse(&text.to_lowercase());
+
+    let mut normalized = String::with_capacity(lowered.len());
+    let mut word = String::new();
+    for c in lowered.chars() {
+        if c.is_alphabetic() || (c == '\'' && !word.is_empty()) {
+            word.push(c);
+        } else {
+            normalized.push_str(&correct(&word));
+            word.clear();
+            normalized.push(c);
+        }
+    }
+    normalized.push_str(&correct(&word));
+
+    normalized
+}
+
+// runs of three or more of the same letter squashed down to one, leaving doubled letters
+// alone since plenty of words have them
+fn collapse(text: &str) -> String {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut collapsed = String::with_capacity(text.len());
+
+    let mut i = 0;
+    while i < chars.len() {
+        let run = chars[i..].iter().take_while(|c| **c == chars[i]).count();
+        if chars[i].is_alphabetic() && run >= 3 {
+            collapsed.push(chars[i]);
+        } else {
+            collapsed.extend(&chars[i..i + run]);
+        }
+        i += run;
+    }
+
+    collapsed
+}
+
+// the closest vocabulary word, if `word` looks like a typo of one: one edit away, or two for
+// long vocabulary words, and never for short or real words
+fn correct(word: &str) -> String {
+    if word.chars().count() < MIN_LENGTH
+        || WORDS.iter().any(|w| w == word)
+        || ENGLISH.contains(&word)
+    {
+        return word.to_owned();
+    }
+
+    WORDS
+        .iter()
+        .map(|w| (damerau_levenshtein(word, w), w.chars().count(), w))
+        .filter(|(distance, length, _)| {
+            *length >= MIN_LENGTH && *distance <= length / LETTERS_PER_TYPO
+        })
+        // ties go to the word listed first
+        .min_by_key(|(distance, _, _)| *distance)
+        .map_or_else(|| word.to_owned(), |(_, _, w)| w.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corrects_typos_of_the_vocabulary() {
+        assert_eq!(normalize("Woult never throw"), "would never throw");
+        assert_eq!(normalize("poisened?"), "poisoned?");
+        assert_eq!(normalize("I woukd"), "i would");
+        assert_eq!(normalize("borinnggg"), "boring");
+        assert_eq!(normalize("brucheta!!"), "bruschetta!!");
+        assert_eq!(normalize("malonnnnn"), "melon");
+        assert_eq!(normalize("suprised"), "surprised");
+        assert_eq!(normalize("tomatilo"), "tomatillo");
+        assert_eq!(normalize("stawberry"), "strawberry");
+    }
+
+    #[test]
+    fn leaves_real_words_alone() {
+        for word in [
+            "flesh", "there", "world", "butter", "pearl", "paste", "spice", "threw", "could",
+            "won't", "taste", "plum", "bandana",
+        ] {
+            assert_eq!(normalize(word), word);
+        }
+        assert_eq!(normalize("look at the flesh"), "look at the flesh");
+    }
+
+    #[test]
+    fn keeps_numbers_and_punctuation() {
+        assert_eq!(normalize("3.5, maybe 4"), "3.5, maybe 4");
+        assert_eq!(normalize("FRESH!!!"), "fresh!!!");
+    }
+}
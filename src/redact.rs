@@ -0,0 +1,270 @@
+//! Scrubbing for outputs meant to be published: profanity and contact details come out of
+//! the free text, and respondents' identities are hashed or dropped.
+
+use std::sync::LazyLock;
+
+use clap::ValueEnum;
+use csv::StringRecord;
+use hmac::{Hmac, Mac};
+use regex::Regex;
+use serde::Serialize;
+use sha2::Sha256;
+
+use crate::{METADATA_COLUMNS, Response, Survey};
+
+/// What to do with the User ID and display name columns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Identities {
+    /// replace them with a short keyed hash, so a respondent's rows still line up across outputs
+    #[default]
+    Hash,
+    /// blank them out entirely
+    Drop,
+}
+
+/// What a stretch of redacted text was.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    Identity,
+    Url,
+    Email,
+    Handle,
+    Phone,
+    Profanity,
+}
+
+impl Kind {
+    // what the text is replaced with
+    fn placeholder(self) -> &'static str {
+        match self {
+            Self::Identity => "",
+            Self::Url => "[url]",
+            Self::Email => "[email]",
+            Self::Handle => "[handle]",
+            Self::Phone => "[phone]",
+            Self::Profanity => "[profanity]",
+        }
+    }
+}
+
+/// One redaction, for the redaction log. The log holds exactly what was scrubbed, so it
+/// mustn't be published alongside the outputs.
+#[derive(Debug, Clone, Serialize)]
+pub struct Redaction {
+    /// The line the row started on, or 0 for rows rebuilt from json.
+    pub line: u64,
+    /// The column's header.
+    pub column: String,
+    pub kind: Kind,
+    pub original: String,
+    pub replacement: String,
+}
+
+// checked in this order, so urls are gone before their @s and digits look like anything else
+static PATTERNS: LazyLock<[(Kind, Regex); 5]> = LazyLock::new(|| {
+    [
+        (Kind::Url, r"(?i)\b(https?://|www\.)\S+"),
+        (Kind::Email, r"(?i)\b[\w.+-]+@[\w-]+(\.[\w-]+)+\b"),
+        (Kind::Handle, r"(^|[^\w])@\w{2,}"),
+        (
+            Kind::Phone,
+            r"(\+\d{1,3}[\s.-]?)?\(?\b\d{3}\)?[\s.-]?\d{3}[\s.-]?\d{4}\b",
+        ),
+        (
+            Kind::Profanity,
+            r"(?i)\b(fuck\w*|shit(s|ty|ting|head\w*)?|bitch(es|y|ing)?|assholes?|ass|asf|af|wtf|stfu|damn(ed|it)?|crap(s|py)?|dicks?|dickheads?|piss(ed|es|ing|y)?|bastards?|cunts?|deepthroat\w*)\b",
+        ),
+    ]
+    .map(|(kind, pattern)| (kind, Regex::new(pattern).unwrap()))
+});
+
+/// Scrubs profanity, urls, email addresses, @handles and phone numbers out of `text`, along
+/// with every piece of text that was scrubbed.
+pub fn scrub(text: &str) -> (String, Vec<(Kind, String)>) {
+    let mut text = text.to_owned();
+    let mut found = Vec::new();
+
+    for (kind, regex) in PATTERNS.iter() {
+        if !regex.is_match(&text) {
+            continue;
+        }
+        text = regex
+            .replace_all(&text, |captures: &regex::Captures| {
+                let matched = &captures[0];
+                // a handle's pattern takes the character before the @ along with it
+                let (before, original) = match matched.find('@') {
+                    Some(at) if *kind == Kind::Handle => matched.split_at(at),
+                    _ => ("", matched),
+                };
+                found.push((*kind, original.to_owned()));
+                format!("{before}{}", kind.placeholder())
+            })
+            .into_owned();
+    }
+
+    (text, found)
+}
+
+/// A fresh random key for [`hash`], for runs that weren't given one.
+pub fn generate_key() -> Vec<u8> {
+    let mut key = vec![0; 32];
+    getrandom::fill(&mut key).expect("couldn't get randomness for the identity key");
+    key
+}
+
+/// A short stand-in for an identity, or nothing for an empty one. It's an HMAC keyed with
+/// `key`, so only someone holding the key can check a guessed User ID or name against it: the
+/// key must never be published with the outputs.
+pub fn hash(identity: &str, key: &[u8]) -> String {
+    if identity.is_empty() {
+        return String::new();
+    }
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac takes keys of any length");
+    mac.update(identity.as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .take(6)
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+impl Survey {
+    /// Scrubs the survey for publishing: every answer cell and note goes through [`scrub`],
+    /// and the User ID and display name are hashed with `key` or dropped, everywhere they're
+    /// kept. Each redaction is added to `redactions`, once per cell.
+    ///
+    /// Weights keyed by User ID need working out before this.
+    pub fn redact(&mut self, identities: Identities, key: &[u8]) {
+        let identity = |value: &str| match identities {
+            Identities::Hash => hash(value, key),
+            Identities::Drop => String::new(),
+        };
+        let line = |record: &StringRecord| record.position().map_or(0, |p| p.line());
+
+        let records = self
+            .records
+            .iter_mut()
+            .chain(self.rejects.iter_mut().map(|r| &mut r.record));
+        for record in records {
+            let mut cells = record.iter().map(str::to_owned).collect::<Vec<_>>();
+            for (column, cell) in cells.iter_mut().enumerate() {
+                let found = match column {
+                    0 | 1 if !cell.is_empty() => {
+                        let original = std::mem::replace(cell, identity(cell));
+                        vec![(Kind::Identity, original)]
+                    }
+                    _ if column >= METADATA_COLUMNS => {
+                        let (scrubbed, found) = scrub(cell);
+                        *cell = scrubbed;
+                        found
+                    }
+                    _ => Vec::new(),
+                };
+                self.redactions
+                    .extend(found.into_iter().map(|(kind, original)| Redaction {
+                        line: line(record),
+                        column: self.headers.get(column).unwrap_or_default().to_owned(),
+                        kind,
+                        replacement: if kind == Kind::Identity {
+                            identity(&original)
+                        } else {
+                            kind.placeholder().to_owned()
+                        },
+                        original,
+                    }));
+            }
+
+            let mut scrubbed = StringRecord::from(cells);
+            scrubbed.set_position(record.position().cloned());
+            *record = scrubbed;
+        }
+
+        // notes are copied from the cells, whose redactions are already logged
+        for fruit in self.responses.iter_mut().flat_map(Response::fruits_mut) {
+            fruit.notes = scrub(&fruit.notes).0;
+        }
+
+        for correction in &mut self.corrections {
+            correction.user_id = identity(&correction.user_id);
+            correction.original = scrub(&correction.original).0;
+            correction.corrected = scrub(&correction.corrected).0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str) -> Vec<Kind> {
+        scrub(text).1.into_iter().map(|(kind, _)| kind).collect()
+    }
+
+    #[test]
+    fn scrubs_contact_details() {
+        assert_eq!(
+            scrub("mail me at jo.doe+fruit@example.co.uk").0,
+            "mail me at [email]"
+        );
+        assert_eq!(scrub("call (555) 123-4567 now").0, "call [phone] now");
+        assert_eq!(scrub("+1 555.123.4567").0, "[phone]");
+        assert_eq!(scrub("see https://example.com/x?y=1").0, "see [url]");
+        assert_eq!(scrub("or www.example.com").0, "or [url]");
+        assert_eq!(scrub("ask @fruit_fan!").0, "ask [handle]!");
+        assert_eq!(kinds("jo@example.com"), [Kind::Email]);
+    }
+
+    #[test]
+    fn scrubs_profanity() {
+        assert_eq!(scrub("what the FUCK").0, "what the [profanity]");
+        assert_eq!(kinds("shitty dicks"), [Kind::Profanity, Kind::Profanity]);
+    }
+
+    #[test]
+    fn leaves_ordinary_words_alone() {
+        for text in [
+            "Dickens and Dickinson",
+            "assess the class, pass the grass",
+            "scrap the crappie? no, crab",
+            "shitake mushrooms, like Pissarro painted",
+            "about 4 or 5, in 2023",
+            "a rating of 3.5",
+        ] {
+            assert_eq!(scrub(text), (text.to_owned(), Vec::new()), "{text}");
+        }
+    }
+
+    #[test]
+    fn logs_each_redaction_once() {
+        let headers = ["User ID", "Name", "Timestamp"]
+            .into_iter()
+            .map(str::to_owned)
+            .chain((0..60).map(|i| format!("question {i}")))
+            .collect::<Vec<_>>()
+            .join(",");
+        let answers = ["Yes,mail jo@example.com,2"; 20].join(",");
+        let csv = format!("{headers}\nu1,Jo,today,{answers}\n");
+        let mut survey =
+            Survey::from_source(&mut crate::source::DelimitedSource::new(csv.as_bytes())).unwrap();
+
+        survey.redact(Identities::Hash, b"key");
+
+        let count = |kind| survey.redactions.iter().filter(|r| r.kind == kind).count();
+        assert_eq!(count(Kind::Identity), 2);
+        assert_eq!(count(Kind::Email), 20);
+        assert_eq!(survey.records[0].get(0), Some(hash("u1", b"key").as_str()));
+        assert_eq!(survey.responses[0].fruits()[0].notes, "mail [email]");
+    }
+
+    #[test]
+    fn hashes_are_keyed() {
+        assert_eq!(hash("user 1", b"key"), hash("user 1", b"key"));
+        assert_ne!(hash("user 1", b"key"), hash("user 1", b"other key"));
+        assert_ne!(hash("user 1", b"key"), hash("user 2", b"key"));
+        assert_eq!(hash("user 1", b"key").len(), 12);
+        assert_eq!(hash("", b"key"), "");
+        assert_ne!(generate_key(), generate_key());
+    }
+}
@@ -0,0 +1,1016 @@
+use std::ops::Not;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use statrs::distribution::{ContinuousCDF, StudentsT};
+
+use crate::{Fruit, Question, VecResponse, missing::MissingRow};
+
+/// Weighted summary statistics for one item.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ItemReport {
+    pub item: &'static str,
+    /// How many respondents would throw the item, unweighted.
+    pub would_throw_count: usize,
+    /// How many respondents wouldn't throw the item, unweighted.
+    pub would_not_throw_count: usize,
+    pub weighted_would_throw_count: f64,
+    pub weighted_would_not_throw_count: f64,
+    /// How many respondents wrote they'd never throw the item in place of a rancidness,
+    /// unweighted.
+    pub refusal_count: usize,
+    /// How many respondents said they'd throw the item, yet wrote they never would, unweighted.
+    pub contradiction_count: usize,
+    pub weighted_refusal_count: f64,
+    pub weighted_contradiction_count: f64,
+    pub average_expected_rancidness: f64,
+    pub average_desired_rancidness: f64,
+    pub median_expected_rancidness: f64,
+    pub median_desired_rancidness: f64,
+    pub effective_sample_size: f64,
+    /// How many expected rancidness answers were filled in by imputation.
+    pub expected_imputed: usize,
+    /// How many desired rancidness answers were filled in by imputation.
+    pub desired_imputed: usize,
+    /// Total weight of the expected rancidness answers rounding to 1 through 5.
+    #[serde(skip)]
+    pub expected_histogram: [f64; 5],
+    /// Total weight of the desired rancidness answers rounding to 1 through 5.
+    #[serde(skip)]
+    pub desired_histogram: [f64; 5],
+}
+
+impl ItemReport {
+    /// The weighted share of respondents who would throw the item, from 0 to 1.
+    pub fn throw_share(&self) -> f64 {
+        let total = self.weighted_would_throw_count + self.weighted_would_not_throw_count;
+        if total > 0.0 {
+            self.weighted_would_throw_count / total
+        } else {
+            0.0
+        }
+    }
+
+    /// How much more rancid people want the item than they expect it to be.
+    pub fn rancidness_gap(&self) -> f64 {
+        self.average_desired_rancidness - self.average_expected_rancidness
+    }
+}
+
+/// The items most people would throw first, ties going to whichever they want more rancid.
+pub fn ranking(reports: &[ItemReport]) -> Vec<&ItemReport> {
+    let mut ranked = reports.iter().collect::<Vec<_>>();
+    ranked.sort_by(|a, b| {
+        b.throw_share().total_cmp(&a.throw_share()).then(
+            b.average_desired_rancidness
+                .total_cmp(&a.average_desired_rancidness),
+        )
+    });
+    ranked
+}
+
+/// Below this effective sample size an item's averages are too noisy to lean on.
+pub const LOW_SAMPLE_SIZE: f64 = 10.0;
+/// Above this share of missing answers to either question an item gets flagged.
+pub const HIGH_MISSING_SHARE: f64 = 0.2;
+
+/// A reason to take an item's numbers with a grain of salt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Flag {
+    /// The effective sample size is under [`LOW_SAMPLE_SIZE`].
+    LowSampleSize,
+    /// More than [`HIGH_MISSING_SHARE`] of a rancidness question went unanswered.
+    HighMissingness,
+    /// Someone said they'd throw the item, but wrote they never would.
+    Contradiction,
+}
+
+impl Flag {
+    /// A short human-readable description.
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::LowSampleSize => "low sample size",
+            Self::HighMissingness => "many missing answers",
+            Self::Contradiction => "contradictory refusals",
+        }
+    }
+}
+
+/// Flags an item by its report and its rows of the [`missing::table`](crate::missing::table).
+pub fn flags(report: &ItemReport, missing: &[MissingRow]) -> Vec<Flag> {
+    let mut flags = Vec::new();
+    if report.effective_sample_size < LOW_SAMPLE_SIZE {
+        flags.push(Flag::LowSampleSize);
+    }
+    if missing
+        .iter()
+        .any(|m| m.item == report.item && m.missing_share > HIGH_MISSING_SHARE)
+    {
+        flags.push(Flag::HighMissingness);
+    }
+    if report.contradiction_count > 0 {
+        flags.push(Flag::Contradiction);
+    }
+    flags
+}
+
+/// One bin of an [`ItemReport`] histogram, for long-format output.
+#[derive(Debug, Serialize)]
+pub struct HistogramRow {
+    pub item: &'static str,
+    pub question: Question,
+    pub rancidness: usize,
+    pub weight: f64,
+}
+
+impl HistogramRow {
+    pub fn from_report(report: &ItemReport) -> impl Iterator<Item = Self> {
+        let expected =
+            report
+                .expected_histogram
+                .into_iter()
+                .zip(1..)
+                .map(|(weight, rancidness)| Self {
+                    item: report.item,
+                    question: Question::Expected,
+                    rancidness,
+                    weight,
+                });
+        let desired = report
+            .desired_histogram
+            .into_iter()
+            .zip(1..)
+            .map(|(weight, rancidness)| Self {
+                item: report.item,
+                question: Question::Desired,
+                rancidness,
+                weight,
+            });
+
+        expected.chain(desired)
+    }
+}
+
+/// Summarizes everyone's answers for one item, weighting each respondent by the
+/// matching entry of `weights`. With uniform weights these are plain counts and means.
+pub fn report(item: &'static str, fruits: &[Fruit], weights: &[f64]) -> ItemReport {
+    let weighted = || fruits.iter().zip(weights.iter().copied());
+
+    let (sum, sum_of_squares) = weighted().fold((0.0, 0.0), |(s, sq), (_, w)| (s + w, sq + w * w));
+
+    ItemReport {
+        item,
+        would_throw_count: fruits.iter().filter(|f| f.would_throw).count(),
+        would_not_throw_count: fruits.iter().filter(|f| f.would_throw.not()).count(),
+        // an empty f64 sum comes out as -0
+        weighted_would_throw_count: weighted()
+            .filter(|(f, _)| f.would_throw)
+            .fold(0.0, |sum, (_, w)| sum + w),
+        weighted_would_not_throw_count: weighted()
+            .filter(|(f, _)| f.would_throw.not())
+            .fold(0.0, |sum, (_, w)| sum + w),
+        refusal_count: fruits.iter().filter(|f| f.refused()).count(),
+        contradiction_count: fruits
+            .iter()
+            .filter(|f| f.would_throw && f.refused())
+            .count(),
+        weighted_refusal_count: weighted()
+            .filter(|(f, _)| f.refused())
+            .fold(0.0, |sum, (_, w)| sum + w),
+        weighted_contradiction_count: weighted()
+            .filter(|(f, _)| f.would_throw && f.refused())
+            .fold(0.0, |sum, (_, w)| sum + w),
+        average_expected_rancidness: weighted_mean(
+            weighted().filter_map(|(f, w)| Some((f.expected_rancidness?, w))),
+        ),
+        average_desired_rancidness: weighted_mean(
+            weighted().filter_map(|(f, w)| Some((f.desired_rancidness?, w))),
+        ),
+        median_expected_rancidness: weighted_median(
+            weighted().filter_map(|(f, w)| Some((f.expected_rancidness?, w))),
+        ),
+        median_desired_rancidness: weighted_median(
+            weighted().filter_map(|(f, w)| Some((f.desired_rancidness?, w))),
+        ),
+        effective_sample_size: if sum_of_squares > 0.0 {
+            sum * sum / sum_of_squares
+        } else {
+            0.0
+        },
+        expected_imputed: fruits
+            .iter()
+            .filter(|f| f.is_imputed(Question::Expected))
+            .count(),
+        desired_imputed: fruits
+            .iter()
+            .filter(|f| f.is_imputed(Question::Desired))
+            .count(),
+        expected_histogram: histogram(
+            weighted().filter_map(|(f, w)| Some((f.expected_rancidness?, w))),
+        ),
+        desired_histogram: histogram(
+            weighted().filter_map(|(f, w)| Some((f.desired_rancidness?, w))),
+        ),
+    }
+}
+
+pub(crate) fn weighted_mean(values: impl Iterator<Item = (f64, f64)>) -> f64 {
+    let (sum, total_weight) = values.fold((0.0, 0.0), |(s, t), (v, w)| (s + v * w, t + w));
+
+    if total_weight > 0.0 {
+        sum / total_weight
+    } else {
+        0.0
+    }
+}
+
+// the value half the weight falls either side of, splitting the difference when the halfway
+// point lands exactly between two values
+fn weighted_median(values: impl Iterator<Item = (f64, f64)>) -> f64 {
+    let mut values = values.filter(|(_, w)| *w > 0.0).collect::<Vec<_>>();
+    values.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    let half = values.iter().map(|(_, w)| w).sum::<f64>() / 2.0;
+
+    let mut cumulative = 0.0;
+    for (i, (value, weight)) in values.iter().enumerate() {
+        cumulative += weight;
+        if cumulative > half {
+            return *value;
+        }
+        if cumulative == half {
+            return values
+                .get(i + 1)
+                .map_or(*value, |(next, _)| (value + next) / 2.0);
+        }
+    }
+    0.0
+}
+
+fn histogram(values: impl Iterator<Item = (f64, f64)>) -> [f64; 5] {
+    let mut bins = [0.0; 5];
+    for (value, weight) in values.filter(|(value, _)| value.is_finite()) {
+        // values that escaped massaging still land in the nearest bin on the 1-5 scale
+        bins[(value.round().clamp(1.0, 5.0) as usize) - 1] += weight;
+    }
+    bins
+}
+
+/// A paired t-test of whether people want an item more or less rancid than they expect it
+/// to be.
+#[derive(Debug, Serialize)]
+pub struct GapTest {
+    pub item: &'static str,
+    /// How many respondents answered both rancidness questions themselves.
+    pub pairs: usize,
+    /// The weighted mean of desired minus expected rancidness.
+    pub mean_gap: f64,
+    pub standard_error: f64,
+    pub t: f64,
+    pub degrees_of_freedom: f64,
+    /// The two-sided p-value, or NaN when there are too few pairs to test.
+    pub p_value: f64,
+}
+
+/// Tests the gap between desired and expected rancidness for one item, over the respondents
+/// who answered both questions. Imputed answers are left out, since they'd only pull the
+/// gap towards whatever they were imputed from.
+///
+/// Weights enter through the weighted mean and variance of the gaps, with the effective
+/// sample size standing in for the number of pairs; uniform weights give the textbook test.
+pub fn gap_test(item: &'static str, fruits: &[Fruit], weights: &[f64]) -> GapTest {
+    let gaps = fruits
+        .iter()
+        .zip(weights.iter().copied())
+        .filter(|(f, _)| f.expected_missing.is_none() && f.desired_missing.is_none())
+        .filter_map(|(f, w)| Some((f.desired_rancidness? - f.expected_rancidness?, w)))
+        .collect::<Vec<_>>();
+
+    let (sum, sum_of_squares) = gaps
+        .iter()
+        .fold((0.0, 0.0), |(s, sq), (_, w)| (s + w, sq + w * w));
+    let n = if sum_of_squares > 0.0 {
+        sum * sum / sum_of_squares
+    } else {
+        0.0
+    };
+    let mean_gap = weighted_mean(gaps.iter().copied());
+    let variance = if sum > 0.0 && n > 1.0 {
+        gaps.iter()
+            .map(|(gap, w)| w * (gap - mean_gap).powi(2))
+            .sum::<f64>()
+            / sum
+            * n
+            / (n - 1.0)
+    } else {
+        f64::NAN
+    };
+
+    let standard_error = (variance / n).sqrt();
+    let t = mean_gap / standard_error;
+    let degrees_of_freedom = n - 1.0;
+    let p_value = match StudentsT::new(0.0, 1.0, degrees_of_freedom) {
+        Ok(distribution) if t.is_finite() => 2.0 * distribution.sf(t.abs()),
+        // every pair agreeing exactly leaves no doubt either way
+        _ if standard_error == 0.0 => {
+            if mean_gap == 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        _ => f64::NAN,
+    };
+
+    GapTest {
+        item,
+        pairs: gaps.len(),
+        mean_gap,
+        standard_error,
+        t,
+        degrees_of_freedom,
+        p_value,
+    }
+}
+
+/// Every item's [`ItemReport`] flattened into a single csv row.
+#[derive(Debug, Serialize)]
+pub struct FlattenedReport {
+    artichoke_would_throw_count: usize,
+    artichoke_would_not_throw_count: usize,
+    artichoke_average_expected_rancidness: f64,
+    artichoke_average_desired_rancidness: f64,
+    artichoke_weighted_would_throw_count: f64,
+    artichoke_weighted_would_not_throw_count: f64,
+    artichoke_expected_imputed: usize,
+    artichoke_desired_imputed: usize,
+    artichoke_refusal_count: usize,
+    artichoke_contradiction_count: usize,
+    artichoke_weighted_refusal_count: f64,
+    artichoke_weighted_contradiction_count: f64,
+    avocado_would_throw_count: usize,
+    avocado_would_not_throw_count: usize,
+    avocado_average_expected_rancidness: f64,
+    avocado_average_desired_rancidness: f64,
+    avocado_weighted_would_throw_count: f64,
+    avocado_weighted_would_not_throw_count: f64,
+    avocado_expected_imputed: usize,
+    avocado_desired_imputed: usize,
+    avocado_refusal_count: usize,
+    avocado_contradiction_count: usize,
+    avocado_weighted_refusal_count: f64,
+    avocado_weighted_contradiction_count: f64,
+    banana_would_throw_count: usize,
+    banana_would_not_throw_count: usize,
+    banana_average_expected_rancidness: f64,
+    banana_average_desired_rancidness: f64,
+    banana_weighted_would_throw_count: f64,
+    banana_weighted_would_not_throw_count: f64,
+    banana_expected_imputed: usize,
+    banana_desired_imputed: usize,
+    banana_refusal_count: usize,
+    banana_contradiction_count: usize,
+    banana_weighted_refusal_count: f64,
+    banana_weighted_contradiction_count: f64,
+    brussels_sprout_would_throw_count: usize,
+    brussels_sprout_would_not_throw_count: usize,
+    brussels_sprout_average_expected_rancidness: f64,
+    brussels_sprout_average_desired_rancidness: f64,
+    brussels_sprout_weighted_would_throw_count: f64,
+    brussels_sprout_weighted_would_not_throw_count: f64,
+    brussels_sprout_expected_imputed: usize,
+    brussels_sprout_desired_imputed: usize,
+    brussels_sprout_refusal_count: usize,
+    brussels_sprout_contradiction_count: usize,
+    brussels_sprout_weighted_refusal_count: f64,
+    brussels_sprout_weighted_contradiction_count: f64,
+    cantaloupe_would_throw_count: usize,
+    cantaloupe_would_not_throw_count: usize,
+    cantaloupe_average_expected_rancidness: f64,
+    cantaloupe_average_desired_rancidness: f64,
+    cantaloupe_weighted_would_throw_count: f64,
+    cantaloupe_weighted_would_not_throw_count: f64,
+    cantaloupe_expected_imputed: usize,
+    cantaloupe_desired_imputed: usize,
+    cantaloupe_refusal_count: usize,
+    cantaloupe_contradiction_count: usize,
+    cantaloupe_weighted_refusal_count: f64,
+    cantaloupe_weighted_contradiction_count: f64,
+    cauliflower_would_throw_count: usize,
+    cauliflower_would_not_throw_count: usize,
+    cauliflower_average_expected_rancidness: f64,
+    cauliflower_average_desired_rancidness: f64,
+    cauliflower_weighted_would_throw_count: f64,
+    cauliflower_weighted_would_not_throw_count: f64,
+    cauliflower_expected_imputed: usize,
+    cauliflower_desired_imputed: usize,
+    cauliflower_refusal_count: usize,
+    cauliflower_contradiction_count: usize,
+    cauliflower_weighted_refusal_count: f64,
+    cauliflower_weighted_contradiction_count: f64,
+    chard_would_throw_count: usize,
+    chard_would_not_throw_count: usize,
+    chard_average_expected_rancidness: f64,
+    chard_average_desired_rancidness: f64,
+    chard_weighted_would_throw_count: f64,
+    chard_weighted_would_not_throw_count: f64,
+    chard_expected_imputed: usize,
+    chard_desired_imputed: usize,
+    chard_refusal_count: usize,
+    chard_contradiction_count: usize,
+    chard_weighted_refusal_count: f64,
+    chard_weighted_contradiction_count: f64,
+    crimini_mushroom_would_throw_count: usize,
+    crimini_mushroom_would_not_throw_count: usize,
+    crimini_mushroom_average_expected_rancidness: f64,
+    crimini_mushroom_average_desired_rancidness: f64,
+    crimini_mushroom_weighted_would_throw_count: f64,
+    crimini_mushroom_weighted_would_not_throw_count: f64,
+    crimini_mushroom_expected_imputed: usize,
+    crimini_mushroom_desired_imputed: usize,
+    crimini_mushroom_refusal_count: usize,
+    crimini_mushroom_contradiction_count: usize,
+    crimini_mushroom_weighted_refusal_count: f64,
+    crimini_mushroom_weighted_contradiction_count: f64,
+    golden_beet_would_throw_count: usize,
+    golden_beet_would_not_throw_count: usize,
+    golden_beet_average_expected_rancidness: f64,
+    golden_beet_average_desired_rancidness: f64,
+    golden_beet_weighted_would_throw_count: f64,
+    golden_beet_weighted_would_not_throw_count: f64,
+    golden_beet_expected_imputed: usize,
+    golden_beet_desired_imputed: usize,
+    golden_beet_refusal_count: usize,
+    golden_beet_contradiction_count: usize,
+    golden_beet_weighted_refusal_count: f64,
+    golden_beet_weighted_contradiction_count: f64,
+    jalapeno_would_throw_count: usize,
+    jalapeno_would_not_throw_count: usize,
+    jalapeno_average_expected_rancidness: f64,
+    jalapeno_average_desired_rancidness: f64,
+    jalapeno_weighted_would_throw_count: f64,
+    jalapeno_weighted_would_not_throw_count: f64,
+    jalapeno_expected_imputed: usize,
+    jalapeno_desired_imputed: usize,
+    jalapeno_refusal_count: usize,
+    jalapeno_contradiction_count: usize,
+    jalapeno_weighted_refusal_count: f64,
+    jalapeno_weighted_contradiction_count: f64,
+    kiwi_would_throw_count: usize,
+    kiwi_would_not_throw_count: usize,
+    kiwi_average_expected_rancidness: f64,
+    kiwi_average_desired_rancidness: f64,
+    kiwi_weighted_would_throw_count: f64,
+    kiwi_weighted_would_not_throw_count: f64,
+    kiwi_expected_imputed: usize,
+    kiwi_desired_imputed: usize,
+    kiwi_refusal_count: usize,
+    kiwi_contradiction_count: usize,
+    kiwi_weighted_refusal_count: f64,
+    kiwi_weighted_contradiction_count: f64,
+    korean_melon_would_throw_count: usize,
+    korean_melon_would_not_throw_count: usize,
+    korean_melon_average_expected_rancidness: f64,
+    korean_melon_average_desired_rancidness: f64,
+    korean_melon_weighted_would_throw_count: f64,
+    korean_melon_weighted_would_not_throw_count: f64,
+    korean_melon_expected_imputed: usize,
+    korean_melon_desired_imputed: usize,
+    korean_melon_refusal_count: usize,
+    korean_melon_contradiction_count: usize,
+    korean_melon_weighted_refusal_count: f64,
+    korean_melon_weighted_contradiction_count: f64,
+    lime_would_throw_count: usize,
+    lime_would_not_throw_count: usize,
+    lime_average_expected_rancidness: f64,
+    lime_average_desired_rancidness: f64,
+    lime_weighted_would_throw_count: f64,
+    lime_weighted_would_not_throw_count: f64,
+    lime_expected_imputed: usize,
+    lime_desired_imputed: usize,
+    lime_refusal_count: usize,
+    lime_contradiction_count: usize,
+    lime_weighted_refusal_count: f64,
+    lime_weighted_contradiction_count: f64,
+    pear_would_throw_count: usize,
+    pear_would_not_throw_count: usize,
+    pear_average_expected_rancidness: f64,
+    pear_average_desired_rancidness: f64,
+    pear_weighted_would_throw_count: f64,
+    pear_weighted_would_not_throw_count: f64,
+    pear_expected_imputed: usize,
+    pear_desired_imputed: usize,
+    pear_refusal_count: usize,
+    pear_contradiction_count: usize,
+    pear_weighted_refusal_count: f64,
+    pear_weighted_contradiction_count: f64,
+    plucot_would_throw_count: usize,
+    plucot_would_not_throw_count: usize,
+    plucot_average_expected_rancidness: f64,
+    plucot_average_desired_rancidness: f64,
+    plucot_weighted_would_throw_count: f64,
+    plucot_weighted_would_not_throw_count: f64,
+    plucot_expected_imputed: usize,
+    plucot_desired_imputed: usize,
+    plucot_refusal_count: usize,
+    plucot_contradiction_count: usize,
+    plucot_weighted_refusal_count: f64,
+    plucot_weighted_contradiction_count: f64,
+    red_grapefruit_would_throw_count: usize,
+    red_grapefruit_would_not_throw_count: usize,
+    red_grapefruit_average_expected_rancidness: f64,
+    red_grapefruit_average_desired_rancidness: f64,
+    red_grapefruit_weighted_would_throw_count: f64,
+    red_grapefruit_weighted_would_not_throw_count: f64,
+    red_grapefruit_expected_imputed: usize,
+    red_grapefruit_desired_imputed: usize,
+    red_grapefruit_refusal_count: usize,
+    red_grapefruit_contradiction_count: usize,
+    red_grapefruit_weighted_refusal_count: f64,
+    red_grapefruit_weighted_contradiction_count: f64,
+    red_onion_would_throw_count: usize,
+    red_onion_would_not_throw_count: usize,
+    red_onion_average_expected_rancidness: f64,
+    red_onion_average_desired_rancidness: f64,
+    red_onion_weighted_would_throw_count: f64,
+    red_onion_weighted_would_not_throw_count: f64,
+    red_onion_expected_imputed: usize,
+    red_onion_desired_imputed: usize,
+    red_onion_refusal_count: usize,
+    red_onion_contradiction_count: usize,
+    red_onion_weighted_refusal_count: f64,
+    red_onion_weighted_contradiction_count: f64,
+    straightneck_squash_would_throw_count: usize,
+    straightneck_squash_would_not_throw_count: usize,
+    straightneck_squash_average_expected_rancidness: f64,
+    straightneck_squash_average_desired_rancidness: f64,
+    straightneck_squash_weighted_would_throw_count: f64,
+    straightneck_squash_weighted_would_not_throw_count: f64,
+    straightneck_squash_expected_imputed: usize,
+    straightneck_squash_desired_imputed: usize,
+    straightneck_squash_refusal_count: usize,
+    straightneck_squash_contradiction_count: usize,
+    straightneck_squash_weighted_refusal_count: f64,
+    straightneck_squash_weighted_contradiction_count: f64,
+    strawberry_would_throw_count: usize,
+    strawberry_would_not_throw_count: usize,
+    strawberry_average_expected_rancidness: f64,
+    strawberry_average_desired_rancidness: f64,
+    strawberry_weighted_would_throw_count: f64,
+    strawberry_weighted_would_not_throw_count: f64,
+    strawberry_expected_imputed: usize,
+    strawberry_desired_imputed: usize,
+    strawberry_refusal_count: usize,
+    strawberry_contradiction_count: usize,
+    strawberry_weighted_refusal_count: f64,
+    strawberry_weighted_contradiction_count: f64,
+    tomatillo_would_throw_count: usize,
+    tomatillo_would_not_throw_count: usize,
+    tomatillo_average_expected_rancidness: f64,
+    tomatillo_average_desired_rancidness: f64,
+    tomatillo_weighted_would_throw_count: f64,
+    tomatillo_weighted_would_not_throw_count: f64,
+    tomatillo_expected_imputed: usize,
+    tomatillo_desired_imputed: usize,
+    tomatillo_refusal_count: usize,
+    tomatillo_contradiction_count: usize,
+    tomatillo_weighted_refusal_count: f64,
+    tomatillo_weighted_contradiction_count: f64,
+}
+
+impl FlattenedReport {
+    pub fn map(reports: &[ItemReport; 20]) -> Self {
+        let [
+            artichoke,
+            avocado,
+            banana,
+            brussels_sprout,
+            cantaloupe,
+            cauliflower,
+            chard,
+            crimini_mushroom,
+            golden_beet,
+            jalapeno,
+            kiwi,
+            korean_melon,
+            lime,
+            pear,
+            plucot,
+            red_grapefruit,
+            red_onion,
+            straightneck_squash,
+            strawberry,
+            tomatillo,
+        ] = reports;
+
+        Self {
+            artichoke_would_throw_count: artichoke.would_throw_count,
+            artichoke_would_not_throw_count: artichoke.would_not_throw_count,
+            artichoke_average_expected_rancidness: artichoke.average_expected_rancidness,
+            artichoke_average_desired_rancidness: artichoke.average_desired_rancidness,
+            artichoke_weighted_would_throw_count: artichoke.weighted_would_throw_count,
+            artichoke_weighted_would_not_throw_count: artichoke.weighted_would_not_throw_count,
+            artichoke_expected_imputed: artichoke.expected_imputed,
+            artichoke_desired_imputed: artichoke.desired_imputed,
+            artichoke_refusal_count: artichoke.refusal_count,
+            artichoke_contradiction_count: artichoke.contradiction_count,
+            artichoke_weighted_refusal_count: artichoke.weighted_refusal_count,
+            artichoke_weighted_contradiction_count: artichoke.weighted_contradiction_count,
+            avocado_would_throw_count: avocado.would_throw_count,
+            avocado_would_not_throw_count: avocado.would_not_throw_count,
+            avocado_average_expected_rancidness: avocado.average_expected_rancidness,
+            avocado_average_desired_rancidness: avocado.average_desired_rancidness,
+            avocado_weighted_would_throw_count: avocado.weighted_would_throw_count,
+            avocado_weighted_would_not_throw_count: avocado.weighted_would_not_throw_count,
+            avocado_expected_imputed: avocado.expected_imputed,
+            avocado_desired_imputed: avocado.desired_imputed,
+            avocado_refusal_count: avocado.refusal_count,
+            avocado_contradiction_count: avocado.contradiction_count,
+            avocado_weighted_refusal_count: avocado.weighted_refusal_count,
+            avocado_weighted_contradiction_count: avocado.weighted_contradiction_count,
+            banana_would_throw_count: banana.would_throw_count,
+            banana_would_not_throw_count: banana.would_not_throw_count,
+            banana_average_expected_rancidness: banana.average_expected_rancidness,
+            banana_average_desired_rancidness: banana.average_desired_rancidness,
+            banana_weighted_would_throw_count: banana.weighted_would_throw_count,
+            banana_weighted_would_not_throw_count: banana.weighted_would_not_throw_count,
+            banana_expected_imputed: banana.expected_imputed,
+            banana_desired_imputed: banana.desired_imputed,
+            banana_refusal_count: banana.refusal_count,
+            banana_contradiction_count: banana.contradiction_count,
+            banana_weighted_refusal_count: banana.weighted_refusal_count,
+            banana_weighted_contradiction_count: banana.weighted_contradiction_count,
+            brussels_sprout_would_throw_count: brussels_sprout.would_throw_count,
+            brussels_sprout_would_not_throw_count: brussels_sprout.would_not_throw_count,
+            brussels_sprout_average_expected_rancidness: brussels_sprout
+                .average_expected_rancidness,
+            brussels_sprout_average_desired_rancidness: brussels_sprout.average_desired_rancidness,
+            brussels_sprout_weighted_would_throw_count: brussels_sprout.weighted_would_throw_count,
+            brussels_sprout_weighted_would_not_throw_count: brussels_sprout
+                .weighted_would_not_throw_count,
+            brussels_sprout_expected_imputed: brussels_sprout.expected_imputed,
+            brussels_sprout_desired_imputed: brussels_sprout.desired_imputed,
+            brussels_sprout_refusal_count: brussels_sprout.refusal_count,
+            brussels_sprout_contradiction_count: brussels_sprout.contradiction_count,
+            brussels_sprout_weighted_refusal_count: brussels_sprout.weighted_refusal_count,
+            brussels_sprout_weighted_contradiction_count: brussels_sprout
+                .weighted_contradiction_count,
+            cantaloupe_would_throw_count: cantaloupe.would_throw_count,
+            cantaloupe_would_not_throw_count: cantaloupe.would_not_throw_count,
+            cantaloupe_average_expected_rancidness: cantaloupe.average_expected_rancidness,
+            cantaloupe_average_desired_rancidness: cantaloupe.average_desired_rancidness,
+            cantaloupe_weighted_would_throw_count: cantaloupe.weighted_would_throw_count,
+            cantaloupe_weighted_would_not_throw_count: cantaloupe.weighted_would_not_throw_count,
+            cantaloupe_expected_imputed: cantaloupe.expected_imputed,
+            cantaloupe_desired_imputed: cantaloupe.desired_imputed,
+            cantaloupe_refusal_count: cantaloupe.refusal_count,
+            cantaloupe_contradiction_count: cantaloupe.contradiction_count,
+            cantaloupe_weighted_refusal_count: cantaloupe.weighted_refusal_count,
+            cantaloupe_weighted_contradiction_count: cantaloupe.weighted_contradiction_count,
+            cauliflower_would_throw_count: cauliflower.would_throw_count,
+            cauliflower_would_not_throw_count: cauliflower.would_not_throw_count,
+            cauliflower_average_expected_rancidness: cauliflower.average_expected_rancidness,
+            cauliflower_average_desired_rancidness: cauliflower.average_desired_rancidness,
+            cauliflower_weighted_would_throw_count: cauliflower.weighted_would_throw_count,
+            cauliflower_weighted_would_not_throw_count: cauliflower.weighted_would_not_throw_count,
+            cauliflower_expected_imputed: cauliflower.expected_imputed,
+            cauliflower_desired_imputed: cauliflower.desired_imputed,
+            cauliflower_refusal_count: cauliflower.refusal_count,
+            cauliflower_contradiction_count: cauliflower.contradiction_count,
+            cauliflower_weighted_refusal_count: cauliflower.weighted_refusal_count,
+            cauliflower_weighted_contradiction_count: cauliflower.weighted_contradiction_count,
+            chard_would_throw_count: chard.would_throw_count,
+            chard_would_not_throw_count: chard.would_not_throw_count,
+            chard_average_expected_rancidness: chard.average_expected_rancidness,
+            chard_average_desired_rancidness: chard.average_desired_rancidness,
+            chard_weighted_would_throw_count: chard.weighted_would_throw_count,
+            chard_weighted_would_not_throw_count: chard.weighted_would_not_throw_count,
+            chard_expected_imputed: chard.expected_imputed,
+            chard_desired_imputed: chard.desired_imputed,
+            chard_refusal_count: chard.refusal_count,
+            chard_contradiction_count: chard.contradiction_count,
+            chard_weighted_refusal_count: chard.weighted_refusal_count,
+            chard_weighted_contradiction_count: chard.weighted_contradiction_count,
+            crimini_mushroom_would_throw_count: crimini_mushroom.would_throw_count,
+            crimini_mushroom_would_not_throw_count: crimini_mushroom.would_not_throw_count,
+            crimini_mushroom_average_expected_rancidness: crimini_mushroom
+                .average_expected_rancidness,
+            crimini_mushroom_average_desired_rancidness: crimini_mushroom
+                .average_desired_rancidness,
+            crimini_mushroom_weighted_would_throw_count: crimini_mushroom
+                .weighted_would_throw_count,
+            crimini_mushroom_weighted_would_not_throw_count: crimini_mushroom
+                .weighted_would_not_throw_count,
+            crimini_mushroom_expected_imputed: crimini_mushroom.expected_imputed,
+            crimini_mushroom_desired_imputed: crimini_mushroom.desired_imputed,
+            crimini_mushroom_refusal_count: crimini_mushroom.refusal_count,
+            crimini_mushroom_contradiction_count: crimini_mushroom.contradiction_count,
+            crimini_mushroom_weighted_refusal_count: crimini_mushroom.weighted_refusal_count,
+            crimini_mushroom_weighted_contradiction_count: crimini_mushroom
+                .weighted_contradiction_count,
+            golden_beet_would_throw_count: golden_beet.would_throw_count,
+            golden_beet_would_not_throw_count: golden_beet.would_not_throw_count,
+            golden_beet_average_expected_rancidness: golden_beet.average_expected_rancidness,
+            golden_beet_average_desired_rancidness: golden_beet.average_desired_rancidness,
+            golden_beet_weighted_would_throw_count: golden_beet.weighted_would_throw_count,
+            golden_beet_weighted_would_not_throw_count: golden_beet.weighted_would_not_throw_count,
+            golden_beet_expected_imputed: golden_beet.expected_imputed,
+            golden_beet_desired_imputed: golden_beet.desired_imputed,
+            golden_beet_refusal_count: golden_beet.refusal_count,
+            golden_beet_contradiction_count: golden_beet.contradiction_count,
+            golden_beet_weighted_refusal_count: golden_beet.weighted_refusal_count,
+            golden_beet_weighted_contradiction_count: golden_beet.weighted_contradiction_count,
+            jalapeno_would_throw_count: jalapeno.would_throw_count,
+            jalapeno_would_not_throw_count: jalapeno.would_not_throw_count,
+            jalapeno_average_expected_rancidness: jalapeno.average_expected_rancidness,
+            jalapeno_average_desired_rancidness: jalapeno.average_desired_rancidness,
+            jalapeno_weighted_would_throw_count: jalapeno.weighted_would_throw_count,
+            jalapeno_weighted_would_not_throw_count: jalapeno.weighted_would_not_throw_count,
+            jalapeno_expected_imputed: jalapeno.expected_imputed,
+            jalapeno_desired_imputed: jalapeno.desired_imputed,
+            jalapeno_refusal_count: jalapeno.refusal_count,
+            jalapeno_contradiction_count: jalapeno.contradiction_count,
+            jalapeno_weighted_refusal_count: jalapeno.weighted_refusal_count,
+            jalapeno_weighted_contradiction_count: jalapeno.weighted_contradiction_count,
+            kiwi_would_throw_count: kiwi.would_throw_count,
+            kiwi_would_not_throw_count: kiwi.would_not_throw_count,
+            kiwi_average_expected_rancidness: kiwi.average_expected_rancidness,
+            kiwi_average_desired_rancidness: kiwi.average_desired_rancidness,
+            kiwi_weighted_would_throw_count: kiwi.weighted_would_throw_count,
+            kiwi_weighted_would_not_throw_count: kiwi.weighted_would_not_throw_count,
+            kiwi_expected_imputed: kiwi.expected_imputed,
+            kiwi_desired_imputed: kiwi.desired_imputed,
+            kiwi_refusal_count: kiwi.refusal_count,
+            kiwi_contradiction_count: kiwi.contradiction_count,
+            kiwi_weighted_refusal_count: kiwi.weighted_refusal_count,
+            kiwi_weighted_contradiction_count: kiwi.weighted_contradiction_count,
+            korean_melon_would_throw_count: korean_melon.would_throw_count,
+            korean_melon_would_not_throw_count: korean_melon.would_not_throw_count,
+            korean_melon_average_expected_rancidness: korean_melon.average_expected_rancidness,
+            korean_melon_average_desired_rancidness: korean_melon.average_desired_rancidness,
+            korean_melon_weighted_would_throw_count: korean_melon.weighted_would_throw_count,
+            korean_melon_weighted_would_not_throw_count: korean_melon
+                .weighted_would_not_throw_count,
+            korean_melon_expected_imputed: korean_melon.expected_imputed,
+            korean_melon_desired_imputed: korean_melon.desired_imputed,
+            korean_melon_refusal_count: korean_melon.refusal_count,
+            korean_melon_contradiction_count: korean_melon.contradiction_count,
+            korean_melon_weighted_refusal_count: korean_melon.weighted_refusal_count,
+            korean_melon_weighted_contradiction_count: korean_melon.weighted_contradiction_count,
+            lime_would_throw_count: lime.would_throw_count,
+            lime_would_not_throw_count: lime.would_not_throw_count,
+            lime_average_expected_rancidness: lime.average_expected_rancidness,
+            lime_average_desired_rancidness: lime.average_desired_rancidness,
+            lime_weighted_would_throw_count: lime.weighted_would_throw_count,
+            lime_weighted_would_not_throw_count: lime.weighted_would_not_throw_count,
+            lime_expected_imputed: lime.expected_imputed,
+            lime_desired_imputed: lime.desired_imputed,
+            lime_refusal_count: lime.refusal_count,
+            lime_contradiction_count: lime.contradiction_count,
+            lime_weighted_refusal_count: lime.weighted_refusal_count,
+            lime_weighted_contradiction_count: lime.weighted_contradiction_count,
+            pear_would_throw_count: pear.would_throw_count,
+            pear_would_not_throw_count: pear.would_not_throw_count,
+            pear_average_expected_rancidness: pear.average_expected_rancidness,
+            pear_average_desired_rancidness: pear.average_desired_rancidness,
+            pear_weighted_would_throw_count: pear.weighted_would_throw_count,
+            pear_weighted_would_not_throw_count: pear.weighted_would_not_throw_count,
+            pear_expected_imputed: pear.expected_imputed,
+            pear_desired_imputed: pear.desired_imputed,
+            pear_refusal_count: pear.refusal_count,
+            pear_contradiction_count: pear.contradiction_count,
+            pear_weighted_refusal_count: pear.weighted_refusal_count,
+            pear_weighted_contradiction_count: pear.weighted_contradiction_count,
+            plucot_would_throw_count: plucot.would_throw_count,
+            plucot_would_not_throw_count: plucot.would_not_throw_count,
+            plucot_average_expected_rancidness: plucot.average_expected_rancidness,
+            plucot_average_desired_rancidness: plucot.average_desired_rancidness,
+            plucot_weighted_would_throw_count: plucot.weighted_would_throw_count,
+            plucot_weighted_would_not_throw_count: plucot.weighted_would_not_throw_count,
+            plucot_expected_imputed: plucot.expected_imputed,
+            plucot_desired_imputed: plucot.desired_imputed,
+            plucot_refusal_count: plucot.refusal_count,
+            plucot_contradiction_count: plucot.contradiction_count,
+            plucot_weighted_refusal_count: plucot.weighted_refusal_count,
+            plucot_weighted_contradiction_count: plucot.weighted_contradiction_count,
+            red_grapefruit_would_throw_count: red_grapefruit.would_throw_count,
+            red_grapefruit_would_not_throw_count: red_grapefruit.would_not_throw_count,
+            red_grapefruit_average_expected_rancidness: red_grapefruit.average_expected_rancidness,
+            red_grapefruit_average_desired_rancidness: red_grapefruit.average_desired_rancidness,
+            red_grapefruit_weighted_would_throw_count: red_grapefruit.weighted_would_throw_count,
+            red_grapefruit_weighted_would_not_throw_count: red_grapefruit
+                .weighted_would_not_throw_count,
+            red_grapefruit_expected_imputed: red_grapefruit.expected_imputed,
+            red_grapefruit_desired_imputed: red_grapefruit.desired_imputed,
+            red_grapefruit_refusal_count: red_grapefruit.refusal_count,
+            red_grapefruit_contradiction_count: red_grapefruit.contradiction_count,
+            red_grapefruit_weighted_refusal_count: red_grapefruit.weighted_refusal_count,
+            red_grapefruit_weighted_contradiction_count: red_grapefruit
+                .weighted_contradiction_count,
+            red_onion_would_throw_count: red_onion.would_throw_count,
+            red_onion_would_not_throw_count: red_onion.would_not_throw_count,
+            red_onion_average_expected_rancidness: red_onion.average_expected_rancidness,
+            red_onion_average_desired_rancidness: red_onion.average_desired_rancidness,
+            red_onion_weighted_would_throw_count: red_onion.weighted_would_throw_count,
+            red_onion_weighted_would_not_throw_count: red_onion.weighted_would_not_throw_count,
+            red_onion_expected_imputed: red_onion.expected_imputed,
+            red_onion_desired_imputed: red_onion.desired_imputed,
+            red_onion_refusal_count: red_onion.refusal_count,
+            red_onion_contradiction_count: red_onion.contradiction_count,
+            red_onion_weighted_refusal_count: red_onion.weighted_refusal_count,
+            red_onion_weighted_contradiction_count: red_onion.weighted_contradiction_count,
+            straightneck_squash_would_throw_count: straightneck_squash.would_throw_count,
+            straightneck_squash_would_not_throw_count: straightneck_squash.would_not_throw_count,
+            straightneck_squash_average_expected_rancidness: straightneck_squash
+                .average_expected_rancidness,
+            straightneck_squash_average_desired_rancidness: straightneck_squash
+                .average_desired_rancidness,
+            straightneck_squash_weighted_would_throw_count: straightneck_squash
+                .weighted_would_throw_count,
+            straightneck_squash_weighted_would_not_throw_count: straightneck_squash
+                .weighted_would_not_throw_count,
+            straightneck_squash_expected_imputed: straightneck_squash.expected_imputed,
+            straightneck_squash_desired_imputed: straightneck_squash.desired_imputed,
+            straightneck_squash_refusal_count: straightneck_squash.refusal_count,
+            straightneck_squash_contradiction_count: straightneck_squash.contradiction_count,
+            straightneck_squash_weighted_refusal_count: straightneck_squash.weighted_refusal_count,
+            straightneck_squash_weighted_contradiction_count: straightneck_squash
+                .weighted_contradiction_count,
+            strawberry_would_throw_count: strawberry.would_throw_count,
+            strawberry_would_not_throw_count: strawberry.would_not_throw_count,
+            strawberry_average_expected_rancidness: strawberry.average_expected_rancidness,
+            strawberry_average_desired_rancidness: strawberry.average_desired_rancidness,
+            strawberry_weighted_would_throw_count: strawberry.weighted_would_throw_count,
+            strawberry_weighted_would_not_throw_count: strawberry.weighted_would_not_throw_count,
+            strawberry_expected_imputed: strawberry.expected_imputed,
+            strawberry_desired_imputed: strawberry.desired_imputed,
+            strawberry_refusal_count: strawberry.refusal_count,
+            strawberry_contradiction_count: strawberry.contradiction_count,
+            strawberry_weighted_refusal_count: strawberry.weighted_refusal_count,
+            strawberry_weighted_contradiction_count: strawberry.weighted_contradiction_count,
+            tomatillo_would_throw_count: tomatillo.would_throw_count,
+            tomatillo_would_not_throw_count: tomatillo.would_not_throw_count,
+            tomatillo_average_expected_rancidness: tomatillo.average_expected_rancidness,
+            tomatillo_average_desired_rancidness: tomatillo.average_desired_rancidness,
+            tomatillo_weighted_would_throw_count: tomatillo.weighted_would_throw_count,
+            tomatillo_weighted_would_not_throw_count: tomatillo.weighted_would_not_throw_count,
+            tomatillo_expected_imputed: tomatillo.expected_imputed,
+            tomatillo_desired_imputed: tomatillo.desired_imputed,
+            tomatillo_refusal_count: tomatillo.refusal_count,
+            tomatillo_contradiction_count: tomatillo.contradiction_count,
+            tomatillo_weighted_refusal_count: tomatillo.weighted_refusal_count,
+            tomatillo_weighted_contradiction_count: tomatillo.weighted_contradiction_count,
+        }
+    }
+}
+
+impl VecResponse {
+    /// Reports on every item, see [`report`].
+    pub fn report(&self, weights: &[f64]) -> [ItemReport; 20] {
+        self.items()
+            .map(|(item, fruits)| report(item, fruits, weights))
+    }
+
+    /// Tests every item's rancidness gap, see [`gap_test`].
+    pub fn gap_tests(&self, weights: &[f64]) -> [GapTest; 20] {
+        self.items()
+            .map(|(item, fruits)| gap_test(item, fruits, weights))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fruit(would_throw: bool, expected: Option<f64>, desired: Option<f64>) -> Fruit {
+        Fruit {
+            would_throw,
+            expected_rancidness: expected,
+            desired_rancidness: desired,
+            notes: String::new(),
+            expected_missing: None,
+            desired_missing: None,
+            imputation: None,
+            corrected: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn counts_stay_unweighted() {
+        let fruits = [
+            fruit(true, Some(1.0), Some(2.0)),
+            fruit(true, Some(3.0), Some(4.0)),
+            fruit(false, None, None),
+        ];
+        let report = report("kiwi", &fruits, &[2.0, 0.5, 1.5]);
+
+        assert_eq!(report.would_throw_count, 2);
+        assert_eq!(report.would_not_throw_count, 1);
+        assert_eq!(report.weighted_would_throw_count, 2.5);
+        assert_eq!(report.weighted_would_not_throw_count, 1.5);
+    }
+
+    #[test]
+    fn refusals_are_counted_both_ways() {
+        let mut contradiction = fruit(true, Some(1.0), None);
+        contradiction.desired_missing = Some(crate::missing::Missing::Refused);
+        let mut refusal = fruit(false, None, None);
+        refusal.expected_missing = Some(crate::missing::Missing::Refused);
+        let fruits = [contradiction, refusal, fruit(true, Some(1.0), Some(1.0))];
+        let report = report("kiwi", &fruits, &[2.0, 0.5, 1.0]);
+
+        assert_eq!(report.refusal_count, 2);
+        assert_eq!(report.contradiction_count, 1);
+        assert_eq!(report.weighted_refusal_count, 2.5);
+        assert_eq!(report.weighted_contradiction_count, 2.0);
+        assert_eq!(
+            flags(&report, &[]),
+            [Flag::LowSampleSize, Flag::Contradiction]
+        );
+    }
+
+    #[test]
+    fn effective_sample_size_is_kish() {
+        let fruits = [
+            fruit(true, Some(1.0), Some(1.0)),
+            fruit(true, Some(1.0), Some(1.0)),
+            fruit(false, Some(1.0), Some(1.0)),
+        ];
+
+        assert_eq!(
+            report("kiwi", &fruits, &[1.0; 3]).effective_sample_size,
+            3.0
+        );
+        // (1 + 1 + 2)^2 / (1 + 1 + 4)
+        let weighted = report("kiwi", &fruits, &[1.0, 1.0, 2.0]).effective_sample_size;
+        assert!((weighted - 16.0 / 6.0).abs() < 1e-12);
+        assert_eq!(
+            report("kiwi", &fruits, &[0.0; 3]).effective_sample_size,
+            0.0
+        );
+    }
+
+    #[test]
+    fn weighted_medians() {
+        let median = |values: &[(f64, f64)]| weighted_median(values.iter().copied());
+
+        assert_eq!(median(&[(1.0, 1.0), (2.0, 1.0), (5.0, 1.0)]), 2.0);
+        assert_eq!(
+            median(&[(1.0, 1.0), (2.0, 1.0), (4.0, 1.0), (5.0, 1.0)]),
+            3.0
+        );
+        assert_eq!(median(&[(1.0, 3.0), (5.0, 1.0), (4.0, 1.0)]), 1.0);
+        assert_eq!(median(&[(1.0, 0.0), (5.0, 1.0)]), 5.0);
+        assert_eq!(median(&[]), 0.0);
+    }
+
+    #[test]
+    fn histograms_bin_by_rounded_rancidness() {
+        let bins = histogram([(1.2, 1.0), (4.6, 2.0), (7.0, 0.5), (f64::NAN, 1.0)].into_iter());
+        assert_eq!(bins, [1.0, 0.0, 0.0, 0.0, 2.5]);
+    }
+
+    #[test]
+    fn gap_test_matches_paired_t_test() {
+        // gaps of 1, 2 and 3: mean 2, standard error 1/sqrt(3), t = 2 * sqrt(3) on 2 degrees
+        // of freedom
+        let fruits = [
+            fruit(true, Some(1.0), Some(2.0)),
+            fruit(true, Some(1.0), Some(3.0)),
+            fruit(true, Some(1.0), Some(4.0)),
+            fruit(true, None, Some(5.0)),
+        ];
+        let test = gap_test("kiwi", &fruits, &[1.0; 4]);
+
+        assert_eq!(test.pairs, 3);
+        assert_eq!(test.mean_gap, 2.0);
+        assert!((test.t - 2.0 * 3f64.sqrt()).abs() < 1e-12);
+        assert_eq!(test.degrees_of_freedom, 2.0);
+        assert!((test.p_value - 0.0742).abs() < 1e-4);
+    }
+
+    #[test]
+    fn gap_test_leaves_out_imputed_answers() {
+        let mut imputed = fruit(true, Some(1.0), Some(5.0));
+        imputed.desired_missing = Some(crate::missing::Missing::Blank);
+        let fruits = [
+            fruit(true, Some(2.0), Some(2.0)),
+            fruit(true, Some(3.0), Some(3.0)),
+            imputed,
+        ];
+        let test = gap_test("kiwi", &fruits, &[1.0; 3]);
+
+        assert_eq!(test.pairs, 2);
+        assert_eq!(test.mean_gap, 0.0);
+        assert_eq!(test.p_value, 1.0);
+        assert!(gap_test("kiwi", &fruits[..1], &[1.0]).p_value.is_nan());
+    }
+
+    #[test]
+    fn empty_counts_are_positive_zero() {
+        let report = report("kiwi", &[fruit(false, None, None)], &[1.0]);
+
+        assert!(report.weighted_would_throw_count.is_sign_positive());
+        assert!(report.weighted_refusal_count.is_sign_positive());
+    }
+}
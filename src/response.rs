@@ -0,0 +1,516 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{Fruit, missing::Missing};
+
+/// The name of every item in the survey, in survey order.
+pub const ITEMS: [&str; 20] = [
+    "artichoke",
+    "avocado",
+    "banana",
+    "brussels_sprout",
+    "cantaloupe",
+    "cauliflower",
+    "chard",
+    "crimini_mushroom",
+    "golden_beet",
+    "jalapeno",
+    "kiwi",
+    "korean_melon",
+    "lime",
+    "pear",
+    "plucot",
+    "red_grapefruit",
+    "red_onion",
+    "straightneck_squash",
+    "strawberry",
+    "tomatillo",
+];
+
+/// An item's name as it reads in prose, like "Brussels sprout" for `brussels_sprout`.
+pub fn display_name(item: &str) -> String {
+    let mut chars = item.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+        .unwrap_or_default()
+        .replace('_', " ")
+}
+
+/// One respondent's answers for every item in the survey.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Response {
+    pub artichoke: Fruit,
+    pub avocado: Fruit,
+    pub banana: Fruit,
+    pub brussels_sprout: Fruit,
+    pub cantaloupe: Fruit,
+    pub cauliflower: Fruit,
+    pub chard: Fruit,
+    pub crimini_mushroom: Fruit,
+    pub golden_beet: Fruit,
+    pub jalapeno: Fruit,
+    pub kiwi: Fruit,
+    pub korean_melon: Fruit,
+    pub lime: Fruit,
+    pub pear: Fruit,
+    pub plucot: Fruit,
+    pub red_grapefruit: Fruit,
+    pub red_onion: Fruit,
+    pub straightneck_squash: Fruit,
+    pub strawberry: Fruit,
+    pub tomatillo: Fruit,
+}
+
+impl Response {
+    /// The answers for every item, in survey order.
+    pub fn fruits(&self) -> [&Fruit; 20] {
+        [
+            &self.artichoke,
+            &self.avocado,
+            &self.banana,
+            &self.brussels_sprout,
+            &self.cantaloupe,
+            &self.cauliflower,
+            &self.chard,
+            &self.crimini_mushroom,
+            &self.golden_beet,
+            &self.jalapeno,
+            &self.kiwi,
+            &self.korean_melon,
+            &self.lime,
+            &self.pear,
+            &self.plucot,
+            &self.red_grapefruit,
+            &self.red_onion,
+            &self.straightneck_squash,
+            &self.strawberry,
+            &self.tomatillo,
+        ]
+    }
+
+    pub fn fruits_mut(&mut self) -> [&mut Fruit; 20] {
+        [
+            &mut self.artichoke,
+            &mut self.avocado,
+            &mut self.banana,
+            &mut self.brussels_sprout,
+            &mut self.cantaloupe,
+            &mut self.cauliflower,
+            &mut self.chard,
+            &mut self.crimini_mushroom,
+            &mut self.golden_beet,
+            &mut self.jalapeno,
+            &mut self.kiwi,
+            &mut self.korean_melon,
+            &mut self.lime,
+            &mut self.pear,
+            &mut self.plucot,
+            &mut self.red_grapefruit,
+            &mut self.red_onion,
+            &mut self.straightneck_squash,
+            &mut self.strawberry,
+            &mut self.tomatillo,
+        ]
+    }
+
+    /// Reads every item's answers from the item columns of a survey row.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_iter<'r>(iter: &mut impl Iterator<Item = &'r str>) -> Result<Self, &'static str> {
+        Ok(Self {
+            artichoke: Fruit::from_iter(iter)?,
+            avocado: Fruit::from_iter(iter)?,
+            banana: Fruit::from_iter(iter)?,
+            brussels_sprout: Fruit::from_iter(iter)?,
+            cantaloupe: Fruit::from_iter(iter)?,
+            cauliflower: Fruit::from_iter(iter)?,
+            chard: Fruit::from_iter(iter)?,
+            crimini_mushroom: Fruit::from_iter(iter)?,
+            golden_beet: Fruit::from_iter(iter)?,
+            jalapeno: Fruit::from_iter(iter)?,
+            kiwi: Fruit::from_iter(iter)?,
+            korean_melon: Fruit::from_iter(iter)?,
+            lime: Fruit::from_iter(iter)?,
+            pear: Fruit::from_iter(iter)?,
+            plucot: Fruit::from_iter(iter)?,
+            red_grapefruit: Fruit::from_iter(iter)?,
+            red_onion: Fruit::from_iter(iter)?,
+            straightneck_squash: Fruit::from_iter(iter)?,
+            strawberry: Fruit::from_iter(iter)?,
+            tomatillo: Fruit::from_iter(iter)?,
+        })
+    }
+
+    /// Massages every item's answers, see [`Fruit::massage`].
+    pub fn massage(self) -> Self {
+        Self {
+            artichoke: self.artichoke.massage(),
+            avocado: self.avocado.massage(),
+            banana: self.banana.massage(),
+            brussels_sprout: self.brussels_sprout.massage(),
+            cantaloupe: self.cantaloupe.massage(),
+            cauliflower: self.cauliflower.massage(),
+            chard: self.chard.massage(),
+            crimini_mushroom: self.crimini_mushroom.massage(),
+            golden_beet: self.golden_beet.massage(),
+            jalapeno: self.jalapeno.massage(),
+            kiwi: self.kiwi.massage(),
+            korean_melon: self.korean_melon.massage(),
+            lime: self.lime.massage(),
+            pear: self.pear.massage(),
+            plucot: self.plucot.massage(),
+            red_grapefruit: self.red_grapefruit.massage(),
+            red_onion: self.red_onion.massage(),
+            straightneck_squash: self.straightneck_squash.massage(),
+            strawberry: self.strawberry.massage(),
+            tomatillo: self.tomatillo.massage(),
+        }
+    }
+}
+
+/// A [`Response`] flattened into a single csv row. Each rancidness is followed by why it was
+/// missing, so an imputed value (a rancidness with a reason) stands out from a real answer.
+#[derive(Debug, Serialize)]
+pub struct FlattenedResponse {
+    artichoke_would_throw: bool,
+    artichoke_expected_rancidness: Option<f64>,
+    artichoke_expected_missing: Option<Missing>,
+    artichoke_desired_rancidness: Option<f64>,
+    artichoke_desired_missing: Option<Missing>,
+    avocado_would_throw: bool,
+    avocado_expected_rancidness: Option<f64>,
+    avocado_expected_missing: Option<Missing>,
+    avocado_desired_rancidness: Option<f64>,
+    avocado_desired_missing: Option<Missing>,
+    banana_would_throw: bool,
+    banana_expected_rancidness: Option<f64>,
+    banana_expected_missing: Option<Missing>,
+    banana_desired_rancidness: Option<f64>,
+    banana_desired_missing: Option<Missing>,
+    brussels_sprout_would_throw: bool,
+    brussels_sprout_expected_rancidness: Option<f64>,
+    brussels_sprout_expected_missing: Option<Missing>,
+    brussels_sprout_desired_rancidness: Option<f64>,
+    brussels_sprout_desired_missing: Option<Missing>,
+    cantaloupe_would_throw: bool,
+    cantaloupe_expected_rancidness: Option<f64>,
+    cantaloupe_expected_missing: Option<Missing>,
+    cantaloupe_desired_rancidness: Option<f64>,
+    cantaloupe_desired_missing: Option<Missing>,
+    cauliflower_would_throw: bool,
+    cauliflower_expected_rancidness: Option<f64>,
+    cauliflower_expected_missing: Option<Missing>,
+    cauliflower_desired_rancidness: Option<f64>,
+    cauliflower_desired_missing: Option<Missing>,
+    chard_would_throw: bool,
+    chard_expected_rancidness: Option<f64>,
+    chard_expected_missing: Option<Missing>,
+    chard_desired_rancidness: Option<f64>,
+    chard_desired_missing: Option<Missing>,
+    crimini_mushroom_would_throw: bool,
+    crimini_mushroom_expected_rancidness: Option<f64>,
+    crimini_mushroom_expected_missing: Option<Missing>,
+    crimini_mushroom_desired_rancidness: Option<f64>,
+    crimini_mushroom_desired_missing: Option<Missing>,
+    golden_beet_would_throw: bool,
+    golden_beet_expected_rancidness: Option<f64>,
+    golden_beet_expected_missing: Option<Missing>,
+    golden_beet_desired_rancidness: Option<f64>,
+    golden_beet_desired_missing: Option<Missing>,
+    jalapeno_would_throw: bool,
+    jalapeno_expected_rancidness: Option<f64>,
+    jalapeno_expected_missing: Option<Missing>,
+    jalapeno_desired_rancidness: Option<f64>,
+    jalapeno_desired_missing: Option<Missing>,
+    kiwi_would_throw: bool,
+    kiwi_expected_rancidness: Option<f64>,
+    kiwi_expected_missing: Option<Missing>,
+    kiwi_desired_rancidness: Option<f64>,
+    kiwi_desired_missing: Option<Missing>,
+    korean_melon_would_throw: bool,
+    korean_melon_expected_rancidness: Option<f64>,
+    korean_melon_expected_missing: Option<Missing>,
+    korean_melon_desired_rancidness: Option<f64>,
+    korean_melon_desired_missing: Option<Missing>,
+    lime_would_throw: bool,
+    lime_expected_rancidness: Option<f64>,
+    lime_expected_missing: Option<Missing>,
+    lime_desired_rancidness: Option<f64>,
+    lime_desired_missing: Option<Missing>,
+    pear_would_throw: bool,
+    pear_expected_rancidness: Option<f64>,
+    pear_expected_missing: Option<Missing>,
+    pear_desired_rancidness: Option<f64>,
+    pear_desired_missing: Option<Missing>,
+    plucot_would_throw: bool,
+    plucot_expected_rancidness: Option<f64>,
+    plucot_expected_missing: Option<Missing>,
+    plucot_desired_rancidness: Option<f64>,
+    plucot_desired_missing: Option<Missing>,
+    red_grapefruit_would_throw: bool,
+    red_grapefruit_expected_rancidness: Option<f64>,
+    red_grapefruit_expected_missing: Option<Missing>,
+    red_grapefruit_desired_rancidness: Option<f64>,
+    red_grapefruit_desired_missing: Option<Missing>,
+    red_onion_would_throw: bool,
+    red_onion_expected_rancidness: Option<f64>,
+    red_onion_expected_missing: Option<Missing>,
+    red_onion_desired_rancidness: Option<f64>,
+    red_onion_desired_missing: Option<Missing>,
+    straightneck_squash_would_throw: bool,
+    straightneck_squash_expected_rancidness: Option<f64>,
+    straightneck_squash_expected_missing: Option<Missing>,
+    straightneck_squash_desired_rancidness: Option<f64>,
+    straightneck_squash_desired_missing: Option<Missing>,
+    strawberry_would_throw: bool,
+    strawberry_expected_rancidness: Option<f64>,
+    strawberry_expected_missing: Option<Missing>,
+    strawberry_desired_rancidness: Option<f64>,
+    strawberry_desired_missing: Option<Missing>,
+    tomatillo_would_throw: bool,
+    tomatillo_expected_rancidness: Option<f64>,
+    tomatillo_expected_missing: Option<Missing>,
+    tomatillo_desired_rancidness: Option<f64>,
+    tomatillo_desired_missing: Option<Missing>,
+}
+
+impl FlattenedResponse {
+    pub fn map(response: &Response) -> Self {
+        Self {
+            artichoke_would_throw: response.artichoke.would_throw,
+            artichoke_expected_rancidness: response.artichoke.expected_rancidness,
+            artichoke_expected_missing: response.artichoke.expected_missing,
+            artichoke_desired_rancidness: response.artichoke.desired_rancidness,
+            artichoke_desired_missing: response.artichoke.desired_missing,
+            avocado_would_throw: response.avocado.would_throw,
+            avocado_expected_rancidness: response.avocado.expected_rancidness,
+            avocado_expected_missing: response.avocado.expected_missing,
+            avocado_desired_rancidness: response.avocado.desired_rancidness,
+            avocado_desired_missing: response.avocado.desired_missing,
+            banana_would_throw: response.banana.would_throw,
+            banana_expected_rancidness: response.banana.expected_rancidness,
+            banana_expected_missing: response.banana.expected_missing,
+            banana_desired_rancidness: response.banana.desired_rancidness,
+            banana_desired_missing: response.banana.desired_missing,
+            brussels_sprout_would_throw: response.brussels_sprout.would_throw,
+            brussels_sprout_expected_rancidness: response.brussels_sprout.expected_rancidness,
+            brussels_sprout_expected_missing: response.brussels_sprout.expected_missing,
+            brussels_sprout_desired_rancidness: response.brussels_sprout.desired_rancidness,
+            brussels_sprout_desired_missing: response.brussels_sprout.desired_missing,
+            cantaloupe_would_throw: response.cantaloupe.would_throw,
+            cantaloupe_expected_rancidness: response.cantaloupe.expected_rancidness,
+            cantaloupe_expected_missing: response.cantaloupe.expected_missing,
+            cantaloupe_desired_rancidness: response.cantaloupe.desired_rancidness,
+            cantaloupe_desired_missing: response.cantaloupe.desired_missing,
+            cauliflower_would_throw: response.cauliflower.would_throw,
+            cauliflower_expected_rancidness: response.cauliflower.expected_rancidness,
+            cauliflower_expected_missing: response.cauliflower.expected_missing,
+            cauliflower_desired_rancidness: response.cauliflower.desired_rancidness,
+            cauliflower_desired_missing: response.cauliflower.desired_missing,
+            chard_would_throw: response.chard.would_throw,
+            chard_expected_rancidness: response.chard.expected_rancidness,
+            chard_expected_missing: response.chard.expected_missing,
+            chard_desired_rancidness: response.chard.desired_rancidness,
+            chard_desired_missing: response.chard.desired_missing,
+            crimini_mushroom_would_throw: response.crimini_mushroom.would_throw,
+            crimini_mushroom_expected_rancidness: response.crimini_mushroom.expected_rancidness,
+            crimini_mushroom_expected_missing: response.crimini_mushroom.expected_missing,
+            crimini_mushroom_desired_rancidness: response.crimini_mushroom.desired_rancidness,
+            crimini_mushroom_desired_missing: response.crimini_mushroom.desired_missing,
+            golden_beet_would_throw: response.golden_beet.would_throw,
+            golden_beet_expected_rancidness: response.golden_beet.expected_rancidness,
+            golden_beet_expected_missing: response.golden_beet.expected_missing,
+            golden_beet_desired_rancidness: response.golden_beet.desired_rancidness,
+            golden_beet_desired_missing: response.golden_beet.desired_missing,
+            jalapeno_would_throw: response.jalapeno.would_throw,
+            jalapeno_expected_rancidness: response.jalapeno.expected_rancidness,
+            jalapeno_expected_missing: response.jalapeno.expected_missing,
+            jalapeno_desired_rancidness: response.jalapeno.desired_rancidness,
+            jalapeno_desired_missing: response.jalapeno.desired_missing,
+            kiwi_would_throw: response.kiwi.would_throw,
+            kiwi_expected_rancidness: response.kiwi.expected_rancidness,
+            kiwi_expected_missing: response.kiwi.expected_missing,
+            kiwi_desired_rancidness: response.kiwi.desired_rancidness,
+            kiwi_desired_missing: response.kiwi.desired_missing,
+            korean_melon_would_throw: response.korean_melon.would_throw,
+            korean_melon_expected_rancidness: response.korean_melon.expected_rancidness,
+            korean_melon_expected_missing: response.korean_melon.expected_missing,
+            korean_melon_desired_rancidness: response.korean_melon.desired_rancidness,
+            korean_melon_desired_missing: response.korean_melon.desired_missing,
+            lime_would_throw: response.lime.would_throw,
+            lime_expected_rancidness: response.lime.expected_rancidness,
+            lime_expected_missing: response.lime.expected_missing,
+            lime_desired_rancidness: response.lime.desired_rancidness,
+            lime_desired_missing: response.lime.desired_missing,
+            pear_would_throw: response.pear.would_throw,
+            pear_expected_rancidness: response.pear.expected_rancidness,
+            pear_expected_missing: response.pear.expected_missing,
+            pear_desired_rancidness: response.pear.desired_rancidness,
+            pear_desired_missing: response.pear.desired_missing,
+            plucot_would_throw: response.plucot.would_throw,
+            plucot_expected_rancidness: response.plucot.expected_rancidness,
+            plucot_expected_missing: response.plucot.expected_missing,
+            plucot_desired_rancidness: response.plucot.desired_rancidness,
+            plucot_desired_missing: response.plucot.desired_missing,
+            red_grapefruit_would_throw: response.red_grapefruit.would_throw,
+            red_grapefruit_expected_rancidness: response.red_grapefruit.expected_rancidness,
+            red_grapefruit_expected_missing: response.red_grapefruit.expected_missing,
+            red_grapefruit_desired_rancidness: response.red_grapefruit.desired_rancidness,
+            red_grapefruit_desired_missing: response.red_grapefruit.desired_missing,
+            red_onion_would_throw: response.red_onion.would_throw,
+            red_onion_expected_rancidness: response.red_onion.expected_rancidness,
+            red_onion_expected_missing: response.red_onion.expected_missing,
+            red_onion_desired_rancidness: response.red_onion.desired_rancidness,
+            red_onion_desired_missing: response.red_onion.desired_missing,
+            straightneck_squash_would_throw: response.straightneck_squash.would_throw,
+            straightneck_squash_expected_rancidness: response
+                .straightneck_squash
+                .expected_rancidness,
+            straightneck_squash_expected_missing: response.straightneck_squash.expected_missing,
+            straightneck_squash_desired_rancidness: response.straightneck_squash.desired_rancidness,
+            straightneck_squash_desired_missing: response.straightneck_squash.desired_missing,
+            strawberry_would_throw: response.strawberry.would_throw,
+            strawberry_expected_rancidness: response.strawberry.expected_rancidness,
+            strawberry_expected_missing: response.strawberry.expected_missing,
+            strawberry_desired_rancidness: response.strawberry.desired_rancidness,
+            strawberry_desired_missing: response.strawberry.desired_missing,
+            tomatillo_would_throw: response.tomatillo.would_throw,
+            tomatillo_expected_rancidness: response.tomatillo.expected_rancidness,
+            tomatillo_expected_missing: response.tomatillo.expected_missing,
+            tomatillo_desired_rancidness: response.tomatillo.desired_rancidness,
+            tomatillo_desired_missing: response.tomatillo.desired_missing,
+        }
+    }
+}
+
+/// Every respondent's answers, grouped by item.
+pub struct VecResponse {
+    pub artichoke: Vec<Fruit>,
+    pub avocado: Vec<Fruit>,
+    pub banana: Vec<Fruit>,
+    pub brussels_sprout: Vec<Fruit>,
+    pub cantaloupe: Vec<Fruit>,
+    pub cauliflower: Vec<Fruit>,
+    pub chard: Vec<Fruit>,
+    pub crimini_mushroom: Vec<Fruit>,
+    pub golden_beet: Vec<Fruit>,
+    pub jalapeno: Vec<Fruit>,
+    pub kiwi: Vec<Fruit>,
+    pub korean_melon: Vec<Fruit>,
+    pub lime: Vec<Fruit>,
+    pub pear: Vec<Fruit>,
+    pub plucot: Vec<Fruit>,
+    pub red_grapefruit: Vec<Fruit>,
+    pub red_onion: Vec<Fruit>,
+    pub straightneck_squash: Vec<Fruit>,
+    pub strawberry: Vec<Fruit>,
+    pub tomatillo: Vec<Fruit>,
+}
+
+impl FromIterator<Response> for VecResponse {
+    fn from_iter<I: IntoIterator<Item = Response>>(iter: I) -> Self {
+        let mut artichoke = Vec::default();
+        let mut avocado = Vec::default();
+        let mut banana = Vec::default();
+        let mut brussels_sprout = Vec::default();
+        let mut cantaloupe = Vec::default();
+        let mut cauliflower = Vec::default();
+        let mut chard = Vec::default();
+        let mut crimini_mushroom = Vec::default();
+        let mut golden_beet = Vec::default();
+        let mut jalapeno = Vec::default();
+        let mut kiwi = Vec::default();
+        let mut korean_melon = Vec::default();
+        let mut lime = Vec::default();
+        let mut pear = Vec::default();
+        let mut plucot = Vec::default();
+        let mut red_grapefruit = Vec::default();
+        let mut red_onion = Vec::default();
+        let mut straightneck_squash = Vec::default();
+        let mut strawberry = Vec::default();
+        let mut tomatillo = Vec::default();
+
+        for response in iter {
+            artichoke.push(response.artichoke);
+            avocado.push(response.avocado);
+            banana.push(response.banana);
+            brussels_sprout.push(response.brussels_sprout);
+            cantaloupe.push(response.cantaloupe);
+            cauliflower.push(response.cauliflower);
+            chard.push(response.chard);
+            crimini_mushroom.push(response.crimini_mushroom);
+            golden_beet.push(response.golden_beet);
+            jalapeno.push(response.jalapeno);
+            kiwi.push(response.kiwi);
+            korean_melon.push(response.korean_melon);
+            lime.push(response.lime);
+            pear.push(response.pear);
+            plucot.push(response.plucot);
+            red_grapefruit.push(response.red_grapefruit);
+            red_onion.push(response.red_onion);
+            straightneck_squash.push(response.straightneck_squash);
+            strawberry.push(response.strawberry);
+            tomatillo.push(response.tomatillo);
+        }
+
+        Self {
+            artichoke,
+            avocado,
+            banana,
+            brussels_sprout,
+            cantaloupe,
+            cauliflower,
+            chard,
+            crimini_mushroom,
+            golden_beet,
+            jalapeno,
+            kiwi,
+            korean_melon,
+            lime,
+            pear,
+            plucot,
+            red_grapefruit,
+            red_onion,
+            straightneck_squash,
+            strawberry,
+            tomatillo,
+        }
+    }
+}
+
+impl VecResponse {
+    /// The number of respondents.
+    pub fn len(&self) -> usize {
+        self.artichoke.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Each item's name alongside everyone's answers for it, in survey order.
+    pub fn items(&self) -> [(&'static str, &[Fruit]); 20] {
+        [
+            ("artichoke", &self.artichoke),
+            ("avocado", &self.avocado),
+            ("banana", &self.banana),
+            ("brussels_sprout", &self.brussels_sprout),
+            ("cantaloupe", &self.cantaloupe),
+            ("cauliflower", &self.cauliflower),
+            ("chard", &self.chard),
+            ("crimini_mushroom", &self.crimini_mushroom),
+            ("golden_beet", &self.golden_beet),
+            ("jalapeno", &self.jalapeno),
+            ("kiwi", &self.kiwi),
+            ("korean_melon", &self.korean_melon),
+            ("lime", &self.lime),
+            ("pear", &self.pear),
+            ("plucot", &self.plucot),
+            ("red_grapefruit", &self.red_grapefruit),
+            ("red_onion", &self.red_onion),
+            ("straightneck_squash", &self.straightneck_squash),
+            ("strawberry", &self.strawberry),
+            ("tomatillo", &self.tomatillo),
+        ]
+    }
+}
@@ -0,0 +1,82 @@
+//! JSON Schema documents for the json artifacts, generated from the types they serialize, so
+//! consumers in other languages can check what they're reading.
+
+use std::path::Path;
+
+use clap::ValueEnum;
+use schemars::{JsonSchema, Schema, schema_for};
+use serde_json::Value;
+
+use crate::{Error, ItemReport, Response};
+
+/// A json file the cruncher writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Artifact {
+    /// every response as it was read, before massaging
+    Ingested,
+    /// every response after massaging and any imputation
+    Massaged,
+    /// the per-item summary statistics
+    Report,
+}
+
+impl Artifact {
+    /// The name the default run writes the artifact under.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Ingested => "result_ingested.json",
+            Self::Massaged => "result_massaged.json",
+            Self::Report => "result_summary.json",
+        }
+    }
+
+    /// Tells which artifact a file is from its name, if it's one of the usual ones.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        Self::value_variants()
+            .iter()
+            .copied()
+            .find(|a| a.file_name() == name)
+    }
+
+    /// The artifact's JSON Schema.
+    pub fn schema(self) -> Schema {
+        match self {
+            Self::Ingested => titled::<Vec<Response>>(
+                "Ingested responses",
+                "Every survey response as it was read, one object per respondent. Rancidness \
+                 answers are as written, so may fall outside the 1-5 scale.",
+            ),
+            Self::Massaged => titled::<Vec<Response>>(
+                "Massaged responses",
+                "Every survey response after massaging, one object per respondent. Rancidness \
+                 answers are clamped to the 1-5 scale, and may have been imputed.",
+            ),
+            Self::Report => titled::<Vec<ItemReport>>(
+                "Item report",
+                "Weighted summary statistics for every item, in survey order.",
+            ),
+        }
+    }
+}
+
+fn titled<T: JsonSchema>(title: &str, description: &str) -> Schema {
+    let mut schema = schema_for!(T);
+    schema.insert("title".to_owned(), title.into());
+    schema.insert("description".to_owned(), description.into());
+    schema
+}
+
+/// Checks `instance` against the artifact's schema, describing each violation along with
+/// where in the document it was found. An empty list means the document is valid.
+pub fn validate(artifact: Artifact, instance: &Value) -> Result<Vec<String>, Error> {
+    let validator = jsonschema::validator_for(artifact.schema().as_value())?;
+
+    Ok(validator
+        .iter_errors(instance)
+        .map(|e| {
+            let path = e.instance_path().to_string();
+            format!("{}: {e}", if path.is_empty() { "/" } else { &path })
+        })
+        .collect())
+}
@@ -0,0 +1,254 @@
+//! Lexicon-based sentiment of the free-text notes, and whether it lines up with what
+//! respondents actually answered.
+
+use serde::Serialize;
+
+use crate::{Fruit, VecResponse, normalize::normalize, report::weighted_mean};
+
+/// Words carrying sentiment, scored from -5 (very negative) to 5 (very positive).
+pub const LEXICON: &[(&str, f64)] = &[
+    ("amazing", 4.0),
+    ("awesome", 4.0),
+    ("awful", -3.0),
+    ("bad", -3.0),
+    ("best", 3.0),
+    ("boring", -2.0),
+    ("cool", 1.0),
+    ("cruel", -3.0),
+    ("delicious", 3.0),
+    ("disgusting", -3.0),
+    ("dislike", -2.0),
+    ("enjoy", 2.0),
+    ("fine", 1.0),
+    ("fun", 2.0),
+    ("funny", 2.0),
+    ("gross", -3.0),
+    ("good", 3.0),
+    ("great", 3.0),
+    ("happy", 3.0),
+    ("hate", -3.0),
+    ("hurt", -2.0),
+    ("love", 3.0),
+    ("lovely", 3.0),
+    ("nasty", -3.0),
+    ("nice", 3.0),
+    ("perfect", 3.0),
+    ("poisoned", -3.0),
+    ("rotten", -2.0),
+    ("sad", -2.0),
+    ("shit", -4.0),
+    ("sorry", -1.0),
+    ("stupid", -2.0),
+    ("surprised", 1.0),
+    ("tasty", 3.0),
+    ("terrible", -3.0),
+    ("useful", 2.0),
+    ("waste", -1.0),
+    ("weird", -1.0),
+    ("worst", -3.0),
+    ("wrong", -2.0),
+    ("yuck", -3.0),
+    ("yum", 3.0),
+    ("yummy", 3.0),
+];
+
+// words flipping the sentiment of the few words after them
+const NEGATIONS: &[&str] = &[
+    "not", "no", "never", "dont", "don't", "doesnt", "doesn't", "isnt", "isn't", "wouldnt",
+    "wouldn't", "cant", "can't", "wont", "won't",
+];
+// how many words a negation reaches
+const NEGATION_REACH: usize = 3;
+
+// words strengthening the word after them
+const INTENSIFIERS: &[&str] = &["really", "very", "so", "super", "extremely", "absolutely"];
+const INTENSITY: f64 = 1.5;
+
+// squashes a summed score into -1 to 1, the same way VADER normalizes its compound score
+const NORMALIZATION: f64 = 15.0;
+
+/// Scores a note from -1 (very negative) to 1 (very positive), 0 being neutral or no sentiment
+/// words at all.
+///
+/// The note's spelling is normalized first, see [`normalize`]. Each lexicon word counts with
+/// its score, flipped after a nearby negation and strengthened after an intensifier, and the
+/// sum is squashed into range.
+pub fn score(note: &str) -> f64 {
+    let words = normalize(note)
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+
+    let mut sum = 0.0;
+    for (i, word) in words.iter().enumerate() {
+        // repeated letters are emphasis, and already squashed by `normalize`, so "yummm" reads
+        // as "yum"
+        let Some(&(_, mut value)) = LEXICON.iter().find(|(w, _)| w == word) else {
+            continue;
+        };
+        if i > 0 && INTENSIFIERS.contains(&words[i - 1].as_str()) {
+            value *= INTENSITY;
+        }
+        if words[i.saturating_sub(NEGATION_REACH)..i]
+            .iter()
+            .any(|w| NEGATIONS.contains(&w.as_str()))
+        {
+            value = -value;
+        }
+        sum += value;
+    }
+
+    sum / (sum * sum + NORMALIZATION).sqrt()
+}
+
+/// One respondent's note on one item, with its sentiment.
+#[derive(Debug, Serialize)]
+pub struct NoteSentiment {
+    /// The respondent's position in the survey, counting from 0.
+    pub respondent: usize,
+    pub item: &'static str,
+    pub note: String,
+    pub sentiment: f64,
+}
+
+/// The sentiment of an item's notes, and how it relates to the answers given alongside them.
+#[derive(Debug, Serialize)]
+pub struct ItemSentiment {
+    pub item: &'static str,
+    /// How many respondents left a note.
+    pub notes: usize,
+    pub positive: usize,
+    pub negative: usize,
+    pub neutral: usize,
+    pub mean_sentiment: f64,
+    /// The weighted correlation between a note's sentiment and its respondent saying they'd
+    /// throw the item. Negative when fond notes come with refusing to throw.
+    pub throw_correlation: f64,
+    /// The weighted correlation between a note's sentiment and the desired rancidness, over the
+    /// notes with one.
+    pub desired_correlation: f64,
+}
+
+/// Every non-empty note, item by item, with its sentiment.
+pub fn notes(vec_response: &VecResponse) -> Vec<NoteSentiment> {
+    vec_response
+        .items()
+        .into_iter()
+        .flat_map(|(item, fruits)| {
+            fruits
+                .iter()
+                .enumerate()
+                .filter(|(_, f)| !f.notes.trim().is_empty())
+                .map(move |(respondent, f)| NoteSentiment {
+                    respondent,
+                    item,
+                    note: f.notes.trim().to_owned(),
+                    sentiment: score(&f.notes),
+                })
+        })
+        .collect()
+}
+
+/// Summarizes the sentiment of one item's notes, see [`ItemSentiment`].
+pub fn summary(item: &'static str, fruits: &[Fruit], weights: &[f64]) -> ItemSentiment {
+    let scored = fruits
+        .iter()
+        .zip(weights.iter().copied())
+        .filter(|(f, _)| !f.notes.trim().is_empty())
+        .map(|(f, w)| (f, score(&f.notes), w))
+        .collect::<Vec<_>>();
+
+    ItemSentiment {
+        item,
+        notes: scored.len(),
+        positive: scored.iter().filter(|(_, s, _)| *s > 0.0).count(),
+        negative: scored.iter().filter(|(_, s, _)| *s < 0.0).count(),
+        neutral: scored.iter().filter(|(_, s, _)| *s == 0.0).count(),
+        mean_sentiment: weighted_mean(scored.iter().map(|(_, s, w)| (*s, *w))),
+        throw_correlation: correlation(
+            scored
+                .iter()
+                .map(|(f, s, w)| (*s, if f.would_throw { 1.0 } else { 0.0 }, *w)),
+        ),
+        desired_correlation: correlation(
+            scored
+                .iter()
+                .filter_map(|(f, s, w)| Some((*s, f.desired_rancidness?, *w))),
+        ),
+    }
+}
+
+// weighted pearson correlation of (x, y, weight) triples, NaN when either side doesn't vary
+fn correlation(values: impl Iterator<Item = (f64, f64, f64)>) -> f64 {
+    let values = values.collect::<Vec<_>>();
+    let mean_x = weighted_mean(values.iter().map(|(x, _, w)| (*x, *w)));
+    let mean_y = weighted_mean(values.iter().map(|(_, y, w)| (*y, *w)));
+
+    let (covariance, variance_x, variance_y) =
+        values
+            .iter()
+            .fold((0.0, 0.0, 0.0), |(c, vx, vy), (x, y, w)| {
+                (
+                    c + w * (x - mean_x) * (y - mean_y),
+                    vx + w * (x - mean_x).powi(2),
+                    vy + w * (y - mean_y).powi(2),
+                )
+            });
+
+    if variance_x > 0.0 && variance_y > 0.0 {
+        covariance / (variance_x * variance_y).sqrt()
+    } else {
+        f64::NAN
+    }
+}
+
+impl VecResponse {
+    /// Summarizes the sentiment of every item's notes, in survey order.
+    pub fn sentiment(&self, weights: &[f64]) -> [ItemSentiment; 20] {
+        self.items()
+            .map(|(item, fruits)| summary(item, fruits, weights))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_notes() {
+        assert_eq!(score("it's a potato"), 0.0);
+        assert!(score("delicious") > 0.0);
+        assert!(score("gross") < 0.0);
+        // 3 / sqrt(9 + 15)
+        assert!((score("good") - 3.0 / 24f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn negations_flip_and_intensifiers_strengthen() {
+        assert_eq!(score("not good"), -score("good"));
+        assert_eq!(score("not that i would call it good"), score("good"));
+        assert!(score("very good") > score("good"));
+        assert!(score("really gross") < score("gross"));
+    }
+
+    #[test]
+    fn repeated_letters_are_emphasis() {
+        assert_eq!(score("yummm"), score("yum"));
+        assert_eq!(score("yummmm"), score("yum"));
+    }
+
+    #[test]
+    fn doubled_letters_are_spelling() {
+        // "good" and "cool" aren't "god" and "col", and "fuun" isn't "fun"
+        assert!((score("good") - 3.0 / 24f64.sqrt()).abs() < 1e-12);
+        assert!((score("cool") - 1.0 / 16f64.sqrt()).abs() < 1e-12);
+        assert_eq!(score("fuun"), 0.0);
+    }
+
+    #[test]
+    fn scores_stay_in_range() {
+        let score = score("amazing awesome best delicious great perfect tasty yummy love");
+        assert!(score > 0.9 && score < 1.0);
+    }
+}
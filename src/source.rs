@@ -0,0 +1,428 @@
+//! Where survey rows come from.
+//!
+//! Every source yields a header row followed by raw data rows, which [`Survey`](crate::Survey)
+//! then parses positionally, exactly as it would a csv export.
+
+use std::{
+    convert::Infallible,
+    fs::File,
+    io::{self, BufRead, BufReader, Read},
+    path::Path,
+    str::FromStr,
+};
+
+use calamine::{Data, Ods, Reader, Xlsx, open_workbook};
+use clap::ValueEnum;
+use csv::{Position, ReaderBuilder, StringRecord};
+use serde_json::{Map, Value};
+
+use crate::{Error, ITEMS, schema::Artifact};
+
+/// A source of raw survey rows.
+pub trait ResponseSource {
+    /// The header row, holding the question text for every column.
+    fn headers(&mut self) -> Result<StringRecord, Error>;
+
+    /// The next data row, or `None` once the source is exhausted.
+    fn next_row(&mut self) -> Option<Result<StringRecord, Error>>;
+}
+
+/// The formats survey rows can be read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Comma separated values.
+    Csv,
+    /// Tab separated values.
+    Tsv,
+    /// A json array of objects, one per row, keyed by header.
+    Json,
+    /// One json object per line, keyed by header.
+    Jsonl,
+    /// An OpenDocument spreadsheet.
+    Ods,
+    /// An Office Open XML workbook.
+    Xlsx,
+    /// The cruncher's own result_ingested.json or result_massaged.json (under any name), read
+    /// back in whole rather than row by row.
+    Responses,
+}
+
+impl Format {
+    /// Guesses the format from a file extension, falling back to csv. The cruncher's own
+    /// response artifacts are recognized by name, or by their content when renamed.
+    pub fn from_path(path: &Path) -> Self {
+        if matches!(
+            Artifact::from_path(path),
+            Some(Artifact::Ingested | Artifact::Massaged)
+        ) {
+            return Self::Responses;
+        }
+
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("tsv" | "tab") => Self::Tsv,
+            Some("json") if holds_responses(path) => Self::Responses,
+            Some("json") => Self::Json,
+            Some("jsonl" | "ndjson") => Self::Jsonl,
+            Some("ods") => Self::Ods,
+            Some("xlsx" | "xlsm") => Self::Xlsx,
+            _ => Self::Csv,
+        }
+    }
+}
+
+// whether the json at `path` is an array of the cruncher's own responses rather than of rows
+// keyed by header, which the first item's answers being an object gives away
+fn holds_responses(path: &Path) -> bool {
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    match serde_json::from_reader(BufReader::new(file)) {
+        Ok(Value::Array(rows)) => rows
+            .first()
+            .and_then(|row| row.get(ITEMS[0]))
+            .is_some_and(Value::is_object),
+        _ => false,
+    }
+}
+
+/// Opens `path` as a source of the given format, or the one its extension suggests. `sheet`
+/// is only used by spreadsheets.
+pub fn open(
+    path: &Path,
+    format: Option<Format>,
+    sheet: &Sheet,
+) -> Result<Box<dyn ResponseSource>, Error> {
+    Ok(match format.unwrap_or_else(|| Format::from_path(path)) {
+        Format::Csv => Box::new(DelimitedSource::new(File::open(path)?)),
+        Format::Tsv => Box::new(DelimitedSource::with_delimiter(
+            File::open(path)?,
+            b'\t',
+            b'"',
+        )),
+        Format::Json => Box::new(JsonSource::new(File::open(path)?)?),
+        Format::Jsonl => Box::new(JsonLinesSource::new(File::open(path)?)),
+        Format::Ods => Box::new(SpreadsheetSource::new(
+            open_workbook::<Ods<_>, _>(path).map_err(calamine::Error::from)?,
+            sheet,
+        )?),
+        Format::Xlsx => Box::new(SpreadsheetSource::new(
+            open_workbook::<Xlsx<_>, _>(path).map_err(calamine::Error::from)?,
+            sheet,
+        )?),
+        Format::Responses => {
+            return Err(Error::Source(
+                "response artifacts hold whole responses, not rows",
+            ));
+        }
+    })
+}
+
+/// Delimited text, e.g. csv or tsv.
+pub struct DelimitedSource<R> {
+    reader: csv::Reader<R>,
+}
+
+impl<R: Read> DelimitedSource<R> {
+    /// Reads comma separated values.
+    pub fn new(reader: R) -> Self {
+        Self::with_delimiter(reader, b',', b'"')
+    }
+
+    /// Reads values separated by `delimiter`, quoted with `quote`.
+    pub fn with_delimiter(reader: R, delimiter: u8, quote: u8) -> Self {
+        Self::from_csv(
+            ReaderBuilder::new()
+                .delimiter(delimiter)
+                .quote(quote)
+                // short rows are rejected by the survey rather than failing the whole read
+                .flexible(true)
+                .from_reader(reader),
+        )
+    }
+
+    /// Wraps an already configured csv reader.
+    pub fn from_csv(reader: csv::Reader<R>) -> Self {
+        Self { reader }
+    }
+}
+
+impl<R: Read> ResponseSource for DelimitedSource<R> {
+    fn headers(&mut self) -> Result<StringRecord, Error> {
+        Ok(self.reader.headers()?.clone())
+    }
+
+    fn next_row(&mut self) -> Option<Result<StringRecord, Error>> {
+        let mut record = StringRecord::new();
+        match self.reader.read_record(&mut record) {
+            Ok(true) => Some(Ok(record)),
+            Ok(false) => None,
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+}
+
+/// A json array of row objects keyed by header.
+pub struct JsonSource {
+    headers: StringRecord,
+    rows: std::iter::Enumerate<std::vec::IntoIter<Value>>,
+}
+
+impl JsonSource {
+    /// Reads the whole array up front; the first object's keys become the headers.
+    pub fn new(reader: impl Read) -> Result<Self, Error> {
+        let rows: Vec<Value> = serde_json::from_reader(BufReader::new(reader))?;
+        let headers = match rows.first() {
+            Some(Value::Object(object)) => object.keys().collect(),
+            Some(_) => return Err(Error::Source("json rows must be objects")),
+            None => StringRecord::new(),
+        };
+
+        Ok(Self {
+            headers,
+            rows: rows.into_iter().enumerate(),
+        })
+    }
+}
+
+impl ResponseSource for JsonSource {
+    fn headers(&mut self) -> Result<StringRecord, Error> {
+        Ok(self.headers.clone())
+    }
+
+    fn next_row(&mut self) -> Option<Result<StringRecord, Error>> {
+        let (index, row) = self.rows.next()?;
+
+        Some(match row {
+            // the header row doesn't exist in json, so data rows are numbered from 1
+            Value::Object(object) => Ok(record(&self.headers, &object, index as u64 + 1)),
+            _ => Err(Error::Source("json rows must be objects")),
+        })
+    }
+}
+
+// a row object alongside its zero-based line index
+type JsonRow = (usize, Map<String, Value>);
+
+/// One json row object per line, keyed by header.
+pub struct JsonLinesSource<R> {
+    lines: std::iter::Enumerate<io::Lines<BufReader<R>>>,
+    headers: Option<StringRecord>,
+    first: Option<JsonRow>,
+}
+
+impl<R: Read> JsonLinesSource<R> {
+    /// Reads lazily; the first object's keys become the headers.
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: BufReader::new(reader).lines().enumerate(),
+            headers: None,
+            first: None,
+        }
+    }
+
+    fn next_object(&mut self) -> Option<Result<JsonRow, Error>> {
+        loop {
+            let (index, line) = self.lines.next()?;
+            let line = match line {
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => line,
+                Err(e) => return Some(Err(e.into())),
+            };
+
+            return Some(match serde_json::from_str(&line) {
+                Ok(Value::Object(object)) => Ok((index, object)),
+                Ok(_) => Err(Error::Source("json rows must be objects")),
+                Err(e) => Err(e.into()),
+            });
+        }
+    }
+}
+
+impl<R: Read> ResponseSource for JsonLinesSource<R> {
+    fn headers(&mut self) -> Result<StringRecord, Error> {
+        if let Some(headers) = &self.headers {
+            return Ok(headers.clone());
+        }
+
+        let headers = match self.next_object().transpose()? {
+            Some((index, object)) => {
+                let headers = object.keys().collect();
+                self.first = Some((index, object));
+                headers
+            }
+            None => StringRecord::new(),
+        };
+        self.headers = Some(headers.clone());
+
+        Ok(headers)
+    }
+
+    fn next_row(&mut self) -> Option<Result<StringRecord, Error>> {
+        let headers = match self.headers() {
+            Ok(headers) => headers,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let (index, object) = match self.first.take() {
+            Some(first) => first,
+            None => match self.next_object()? {
+                Ok(next) => next,
+                Err(e) => return Some(Err(e)),
+            },
+        };
+
+        Some(Ok(record(&headers, &object, index as u64 + 1)))
+    }
+}
+
+/// Which sheet of a workbook to read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sheet {
+    /// The zero-based position of the sheet in the workbook.
+    Index(usize),
+    /// The sheet's name.
+    Name(String),
+}
+
+impl Default for Sheet {
+    fn default() -> Self {
+        Self::Index(0)
+    }
+}
+
+impl FromStr for Sheet {
+    type Err = Infallible;
+
+    // anything that looks like a number is taken as an index
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.parse()
+            .map_or_else(|_| Self::Name(s.to_owned()), Self::Index))
+    }
+}
+
+/// One sheet of an ods or xlsx workbook, with the header in its first row.
+pub struct SpreadsheetSource {
+    headers: StringRecord,
+    rows: std::vec::IntoIter<StringRecord>,
+}
+
+impl SpreadsheetSource {
+    /// Reads the whole sheet up front.
+    pub fn new<RS: Read + io::Seek, R>(mut workbook: R, sheet: &Sheet) -> Result<Self, Error>
+    where
+        R: Reader<RS>,
+        calamine::Error: From<R::Error>,
+    {
+        let range = match sheet {
+            Sheet::Index(index) => workbook
+                .worksheet_range_at(*index)
+                .ok_or(Error::Source("no such sheet"))?,
+            Sheet::Name(name) => workbook.worksheet_range(name),
+        }
+        .map_err(calamine::Error::from)?;
+
+        // ranges start at the first used cell, but rows are parsed positionally, so any
+        // empty leading rows and columns have to be put back
+        let (first_row, first_column) = range.start().unwrap_or_default();
+        let mut rows = range.rows().zip(first_row..).map(|(cells, row)| {
+            let mut record = std::iter::repeat_n(String::new(), first_column as usize)
+                .chain(cells.iter().map(cell))
+                .collect::<StringRecord>();
+
+            let mut position = Position::new();
+            position.set_line(row as u64 + 1).set_record(row as u64);
+            record.set_position(Some(position));
+
+            record
+        });
+
+        let headers = rows.next().unwrap_or_default();
+        Ok(Self {
+            headers,
+            rows: rows.collect::<Vec<_>>().into_iter(),
+        })
+    }
+}
+
+impl ResponseSource for SpreadsheetSource {
+    fn headers(&mut self) -> Result<StringRecord, Error> {
+        Ok(self.headers.clone())
+    }
+
+    fn next_row(&mut self) -> Option<Result<StringRecord, Error>> {
+        self.rows.next().map(Ok)
+    }
+}
+
+// renders a typed cell the way it would have appeared in a csv export
+fn cell(data: &Data) -> String {
+    match data {
+        Data::Empty | Data::Error(_) => String::new(),
+        Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => s.clone(),
+        Data::Int(i) => i.to_string(),
+        Data::Float(f) => f.to_string(),
+        Data::Bool(true) => "Yes".to_owned(),
+        Data::Bool(false) => "No".to_owned(),
+        Data::DateTime(date_time) => date_time.as_datetime().map_or_else(
+            || date_time.as_f64().to_string(),
+            |d| d.to_string().replacen(' ', "T", 1),
+        ),
+    }
+}
+
+// lays a row object out in header order, so it parses exactly like a csv row would
+fn record(headers: &StringRecord, object: &Map<String, Value>, line: u64) -> StringRecord {
+    let mut record = headers
+        .iter()
+        .map(|h| match object.get(h) {
+            None | Some(Value::Null) => String::new(),
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Bool(true)) => "Yes".to_owned(),
+            Some(Value::Bool(false)) => "No".to_owned(),
+            Some(other) => other.to_string(),
+        })
+        .collect::<StringRecord>();
+
+    let mut position = Position::new();
+    position.set_line(line).set_record(line - 1);
+    record.set_position(Some(position));
+
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::*;
+
+    #[test]
+    fn recognizes_renamed_responses_by_content() {
+        let dir = env::temp_dir().join(format!("throw-cruncher-source-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let responses = dir.join("wave1.json");
+        fs::write(
+            &responses,
+            r#"[{"artichoke": {"would_throw": true}, "avocado": {"would_throw": false}}]"#,
+        )
+        .unwrap();
+        let rows = dir.join("export.json");
+        fs::write(
+            &rows,
+            r#"[{"User ID": "1", "Would you throw an artichoke?": "Yes"}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(Format::from_path(&responses), Format::Responses);
+        assert_eq!(Format::from_path(&rows), Format::Json);
+        assert_eq!(Format::from_path(&dir.join("missing.json")), Format::Json);
+        assert_eq!(Format::from_path(Path::new("export.tsv")), Format::Tsv);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
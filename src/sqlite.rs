@@ -0,0 +1,155 @@
+//! Exports a survey as a normalized sqlite database.
+//!
+//! Respondents and items each get a table, with one `answers` row per respondent and item
+//! holding the raw cells alongside the ingested and massaged values. The `item_summary` view
+//! recomputes the weighted per-item report in sql.
+
+use std::{fs, io, path::Path};
+
+use crate::{Error, ITEMS, METADATA_COLUMNS, Response, Survey, label};
+use rusqlite::{Connection, params};
+
+const SCHEMA: &str = "
+CREATE TABLE respondents (
+    id INTEGER PRIMARY KEY,
+    line INTEGER,
+    user_id TEXT,
+    display_name TEXT,
+    timestamp TEXT,
+    weight REAL NOT NULL
+);
+
+CREATE TABLE items (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    would_throw_question TEXT,
+    expected_question TEXT,
+    desired_question TEXT
+);
+
+CREATE TABLE answers (
+    respondent_id INTEGER NOT NULL REFERENCES respondents (id),
+    item_id INTEGER NOT NULL REFERENCES items (id),
+    raw_would_throw TEXT,
+    raw_expected_rancidness TEXT,
+    raw_desired_rancidness TEXT,
+    would_throw INTEGER NOT NULL,
+    ingested_expected_rancidness REAL,
+    ingested_desired_rancidness REAL,
+    expected_rancidness REAL,
+    desired_rancidness REAL,
+    expected_missing TEXT,
+    desired_missing TEXT,
+    imputation TEXT,
+    notes TEXT NOT NULL,
+    PRIMARY KEY (respondent_id, item_id)
+);
+
+CREATE VIEW item_summary AS
+SELECT
+    items.name AS item,
+    COUNT(*) AS respondents,
+    SUM(answers.would_throw) AS would_throw_count,
+    SUM(1 - answers.would_throw) AS would_not_throw_count,
+    SUM(respondents.weight * answers.would_throw) AS weighted_would_throw_count,
+    SUM(respondents.weight * (1 - answers.would_throw)) AS weighted_would_not_throw_count,
+    SUM(respondents.weight * answers.expected_rancidness)
+        / SUM(IIF(answers.expected_rancidness IS NULL, NULL, respondents.weight))
+        AS average_expected_rancidness,
+    SUM(respondents.weight * answers.desired_rancidness)
+        / SUM(IIF(answers.desired_rancidness IS NULL, NULL, respondents.weight))
+        AS average_desired_rancidness,
+    COUNT(answers.expected_rancidness) AS expected_answered,
+    COUNT(answers.desired_rancidness) AS desired_answered
+FROM answers
+JOIN items ON items.id = answers.item_id
+JOIN respondents ON respondents.id = answers.respondent_id
+GROUP BY items.id
+ORDER BY items.id;
+";
+
+/// Writes `survey` to a fresh sqlite database at `path`, replacing anything already there.
+///
+/// `survey` is expected to be massaged; `ingested` holds the same responses as they were
+/// before massaging, and `weights` each respondent's weight.
+pub fn export(
+    path: &Path,
+    survey: &Survey,
+    ingested: &[Response],
+    weights: &[f64],
+) -> Result<(), Error> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+        _ => {}
+    }
+
+    let mut connection = Connection::open(path)?;
+    connection.execute_batch(SCHEMA)?;
+
+    let transaction = connection.transaction()?;
+    {
+        let header = |i: usize| survey.headers.get(i);
+        let mut items = transaction.prepare("INSERT INTO items VALUES (?1, ?2, ?3, ?4, ?5)")?;
+        for (id, name) in ITEMS.iter().enumerate() {
+            let column = METADATA_COLUMNS + id * 3;
+            items.execute(params![
+                id,
+                name,
+                header(column),
+                header(column + 1),
+                header(column + 2)
+            ])?;
+        }
+
+        let mut respondents =
+            transaction.prepare("INSERT INTO respondents VALUES (?1, ?2, ?3, ?4, ?5, ?6)")?;
+        let mut answers = transaction.prepare(
+            "INSERT INTO answers VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        )?;
+        for (id, ((record, response), (ingested, weight))) in survey
+            .records
+            .iter()
+            .zip(&survey.responses)
+            .zip(ingested.iter().zip(weights))
+            .enumerate()
+        {
+            let non_empty = |i: usize| record.get(i).filter(|c| !c.is_empty());
+            respondents.execute(params![
+                id,
+                record.position().map(|p| p.line()),
+                non_empty(0),
+                non_empty(1),
+                non_empty(2),
+                weight
+            ])?;
+
+            for (item_id, (fruit, ingested)) in response
+                .fruits()
+                .into_iter()
+                .zip(ingested.fruits())
+                .enumerate()
+            {
+                let column = METADATA_COLUMNS + item_id * 3;
+                answers.execute(params![
+                    id,
+                    item_id,
+                    record.get(column),
+                    record.get(column + 1),
+                    record.get(column + 2),
+                    fruit.would_throw,
+                    ingested.expected_rancidness,
+                    ingested.desired_rancidness,
+                    fruit.expected_rancidness,
+                    fruit.desired_rancidness,
+                    label(fruit.expected_missing),
+                    label(fruit.desired_missing),
+                    label(fruit.imputation),
+                    fruit.notes
+                ])?;
+            }
+        }
+    }
+    transaction.commit()?;
+
+    Ok(())
+}
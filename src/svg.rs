@@ -0,0 +1,474 @@
+//! Hand-rolled svg charts of the report.
+//!
+//! Coordinates are written with a fixed precision and items are laid out in the order given,
+//! so the same report always renders to byte-identical svg.
+
+use std::{fmt::Write as _, fs, path::Path};
+
+use crate::{Error, ItemReport, Question, display_name, escape, keywords::Term};
+
+/// The color the expected rancidness is drawn in.
+pub const EXPECTED_COLOR: &str = "#d95f02";
+/// The color the desired rancidness is drawn in.
+pub const DESIRED_COLOR: &str = "#1b9e77";
+/// The colors the would throw and would not throw counts are drawn in.
+pub const THROW_COLORS: [&str; 2] = ["#7570b3", "#cccccc"];
+/// The colors rancidness levels 1 through 5 are drawn in, from fresh to rancid.
+pub const LEVEL_COLORS: [&str; 5] = ["#0571b0", "#92c5de", "#dddddd", "#f4a582", "#ca0020"];
+
+// horizontal layout shared by the charts with one row per item
+const ROW_HEIGHT: f64 = 18.0;
+const ROW_WIDTH: f64 = 480.0;
+const ROW_LEFT: f64 = 140.0;
+const ROW_RIGHT: f64 = 20.0;
+const ROW_TOP: f64 = 34.0;
+
+const FONT: &str = r#"font-family="sans-serif" font-size="11""#;
+
+/// An item's expected and desired rancidness histograms as side by side bars per level.
+pub fn histogram(report: &ItemReport) -> String {
+    let (width, height) = (280.0, 150.0);
+    let (left, top, bottom) = (30.0, 24.0, 20.0);
+    let plot_height = height - top - bottom;
+    let group_width = (width - left - 10.0) / 5.0;
+    let max = report
+        .expected_histogram
+        .iter()
+        .chain(&report.desired_histogram)
+        .copied()
+        .fold(0.0, f64::max)
+        .max(f64::MIN_POSITIVE);
+
+    let mut svg = open(
+        width,
+        height,
+        &format!("{} rancidness histogram", display_name(report.item)),
+    );
+    legend(
+        &mut svg,
+        left,
+        12.0,
+        &[("expected", EXPECTED_COLOR), ("desired", DESIRED_COLOR)],
+    );
+    axis_line(&mut svg, left, top + plot_height, width - 10.0);
+    let _ = write!(
+        svg,
+        r#"<text x="{:.1}" y="{:.1}" text-anchor="end" {FONT}>{}</text>"#,
+        left - 4.0,
+        top + 4.0,
+        number(max)
+    );
+
+    for (i, (expected, desired)) in report
+        .expected_histogram
+        .iter()
+        .zip(&report.desired_histogram)
+        .enumerate()
+    {
+        let x = left + i as f64 * group_width;
+        let bar_width = group_width * 0.4;
+        for (offset, value, color) in [
+            (group_width * 0.1, expected, EXPECTED_COLOR),
+            (group_width * 0.5, desired, DESIRED_COLOR),
+        ] {
+            let bar_height = value / max * plot_height;
+            let _ = write!(
+                svg,
+                r#"<rect x="{:.1}" y="{:.1}" width="{bar_width:.1}" height="{bar_height:.1}" fill="{color}"><title>{}</title></rect>"#,
+                x + offset,
+                top + plot_height - bar_height,
+                number(*value)
+            );
+        }
+        let _ = write!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle" {FONT}>{}</text>"#,
+            x + group_width / 2.0,
+            height - 6.0,
+            i + 1
+        );
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Every item's average expected and desired rancidness on a shared 1-5 axis, joined by a
+/// line so the gap between the two stands out.
+pub fn dumbbell(reports: &[ItemReport]) -> String {
+    let height = rows_height(reports);
+    let scale = |rancidness: f64| ROW_LEFT + (rancidness - 1.0) / 4.0 * plot_width();
+
+    let mut svg = open(ROW_WIDTH, height, "expected and desired rancidness by item");
+    legend(
+        &mut svg,
+        ROW_LEFT,
+        12.0,
+        &[("expected", EXPECTED_COLOR), ("desired", DESIRED_COLOR)],
+    );
+    for level in 1..=5 {
+        gridline(&mut svg, scale(level as f64), height, &level.to_string());
+    }
+
+    for (i, report) in reports.iter().enumerate() {
+        let y = row_label(&mut svg, i, report);
+        // an item nobody rated averages 0, which has no place on the 1 to 5 axis
+        let on_axis = |rancidness: f64| (1.0..=5.0).contains(&rancidness).then_some(rancidness);
+        let points = [
+            (
+                "expected",
+                EXPECTED_COLOR,
+                on_axis(report.average_expected_rancidness),
+            ),
+            (
+                "desired",
+                DESIRED_COLOR,
+                on_axis(report.average_desired_rancidness),
+            ),
+        ];
+
+        match points.map(|(_, _, rancidness)| rancidness) {
+            [None, None] => {
+                let _ = write!(
+                    svg,
+                    r##"<text x="{:.1}" y="{:.1}" fill="#999" {FONT}>no answers</text>"##,
+                    ROW_LEFT,
+                    y + 4.0
+                );
+                continue;
+            }
+            [Some(expected), Some(desired)] => {
+                let _ = write!(
+                    svg,
+                    r##"<line x1="{:.1}" y1="{y:.1}" x2="{:.1}" y2="{y:.1}" stroke="#999" stroke-width="2"/>"##,
+                    scale(expected),
+                    scale(desired)
+                );
+            }
+            _ => {}
+        }
+        for (question, color, rancidness) in points {
+            if let Some(rancidness) = rancidness {
+                let _ = write!(
+                    svg,
+                    r#"<circle cx="{:.1}" cy="{y:.1}" r="4" fill="{color}"><title>{question} {}</title></circle>"#,
+                    scale(rancidness),
+                    number(rancidness)
+                );
+            }
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Every item's would throw and would not throw counts, stacked into one bar per item and
+/// scaled so each bar spans the whole width.
+pub fn throw_bars(reports: &[ItemReport]) -> String {
+    let height = rows_height(reports);
+
+    let mut svg = open(ROW_WIDTH, height, "would throw by item");
+    legend(
+        &mut svg,
+        ROW_LEFT,
+        12.0,
+        &[
+            ("would throw", THROW_COLORS[0]),
+            ("would not throw", THROW_COLORS[1]),
+        ],
+    );
+    for percent in [0, 50, 100] {
+        gridline(
+            &mut svg,
+            ROW_LEFT + percent as f64 / 100.0 * plot_width(),
+            height,
+            &format!("{percent}%"),
+        );
+    }
+
+    for (i, report) in reports.iter().enumerate() {
+        let y = row_label(&mut svg, i, report);
+        let share = report.throw_share();
+        segments(
+            &mut svg,
+            y,
+            [
+                (
+                    ROW_LEFT,
+                    share,
+                    THROW_COLORS[0],
+                    report.weighted_would_throw_count,
+                ),
+                (
+                    ROW_LEFT + share * plot_width(),
+                    1.0 - share,
+                    THROW_COLORS[1],
+                    report.weighted_would_not_throw_count,
+                ),
+            ],
+        );
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Every item's answers to one rancidness question as a diverging bar, with the fresh end
+/// of the scale to the left of center, the rancid end to the right and the middle level
+/// split across it.
+pub fn likert(reports: &[ItemReport], question: Question) -> String {
+    let height = rows_height(reports);
+    let center = ROW_LEFT + plot_width() / 2.0;
+
+    let title = match question {
+        Question::Expected => "expected rancidness by item",
+        Question::Desired => "desired rancidness by item",
+    };
+    let mut svg = open(ROW_WIDTH, height, title);
+    let levels = ["1", "2", "3", "4", "5"];
+    legend(
+        &mut svg,
+        ROW_LEFT,
+        12.0,
+        &levels.iter().copied().zip(LEVEL_COLORS).collect::<Vec<_>>(),
+    );
+    for percent in [-100i32, -50, 0, 50, 100] {
+        gridline(
+            &mut svg,
+            center + percent as f64 / 200.0 * plot_width(),
+            height,
+            &format!("{}%", percent.abs()),
+        );
+    }
+
+    for (i, report) in reports.iter().enumerate() {
+        let y = row_label(&mut svg, i, report);
+        let histogram = match question {
+            Question::Expected => report.expected_histogram,
+            Question::Desired => report.desired_histogram,
+        };
+        let total = histogram.iter().sum::<f64>();
+        if total <= 0.0 {
+            continue;
+        }
+        let shares = histogram.map(|h| h / total);
+
+        let mut x = center - (shares[0] + shares[1] + shares[2] / 2.0) * plot_width() / 2.0;
+        let mut bars = Vec::with_capacity(5);
+        for ((share, color), weight) in shares.into_iter().zip(LEVEL_COLORS).zip(histogram) {
+            bars.push((x, share / 2.0, color, weight));
+            x += share * plot_width() / 2.0;
+        }
+        segments(&mut svg, y, bars);
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+// the word cloud cycles through the chart colors dark enough to read as text
+const CLOUD_COLORS: [&str; 3] = [EXPECTED_COLOR, DESIRED_COLOR, THROW_COLORS[0]];
+
+/// The single-word `terms` as a word cloud, each sized by its count and laid out in rows in
+/// the order given.
+pub fn word_cloud(terms: &[Term], title: &str) -> String {
+    let width = ROW_WIDTH;
+    let (min_size, max_size) = (10.0, 36.0);
+    let words = terms.iter().filter(|t| t.words == 1).collect::<Vec<_>>();
+    let max = words.iter().map(|t| t.count).max().unwrap_or(1) as f64;
+    let min = words.iter().map(|t| t.count).min().unwrap_or(1) as f64;
+    let size = |count: usize| {
+        if max > min {
+            min_size + (max_size - min_size) * (count as f64 - min) / (max - min)
+        } else {
+            max_size
+        }
+    };
+
+    // flow the words into rows, each row as tall as its biggest word
+    let mut rows = vec![(0.0, 0.0, Vec::new())];
+    for term in &words {
+        let size = size(term.count);
+        // a rough average glyph width for sans-serif, plus a gap
+        let advance = 0.6 * size * term.term.chars().count() as f64 + 10.0;
+        let (row_width, _, _) = rows.last().unwrap();
+        if *row_width + advance > width - 20.0 && !rows.last().unwrap().2.is_empty() {
+            rows.push((0.0, 0.0, Vec::new()));
+        }
+        let (row_width, row_height, row) = rows.last_mut().unwrap();
+        row.push((*row_width, size, *term));
+        *row_width += advance;
+        *row_height = f64::max(*row_height, size);
+    }
+
+    let height = 20.0 + rows.iter().map(|(_, h, _)| h * 1.2).sum::<f64>();
+    let mut svg = open(width, height, title);
+    let mut y = 10.0;
+    for (row_width, row_height, row) in &rows {
+        y += row_height * 1.2;
+        // center each row
+        let left = (width - row_width) / 2.0;
+        for (x, size, term) in row {
+            let _ = write!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" font-family="sans-serif" font-size="{size:.1}" fill="{}"><title>{}</title>{}</text>"#,
+                left + x,
+                y - row_height * 0.2,
+                CLOUD_COLORS[term.count % CLOUD_COLORS.len()],
+                term.count,
+                escape(&term.term)
+            );
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Writes every chart into `directory` as its own svg file, creating the directory if
+/// needed: `throw.svg`, `expected.svg`, `desired.svg` and `dumbbell.svg` across all items,
+/// and a histogram per item named after it.
+pub fn write(directory: &Path, reports: &[ItemReport]) -> Result<(), Error> {
+    fs::create_dir_all(directory)?;
+
+    let overall = [
+        ("throw", throw_bars(reports)),
+        ("expected", likert(reports, Question::Expected)),
+        ("desired", likert(reports, Question::Desired)),
+        ("dumbbell", dumbbell(reports)),
+    ];
+    let items = reports.iter().map(|r| (r.item, histogram(r)));
+    for (name, svg) in overall.into_iter().chain(items) {
+        fs::write(directory.join(name).with_extension("svg"), svg)?;
+    }
+
+    Ok(())
+}
+
+fn plot_width() -> f64 {
+    ROW_WIDTH - ROW_LEFT - ROW_RIGHT
+}
+
+fn rows_height(reports: &[ItemReport]) -> f64 {
+    ROW_TOP + ROW_HEIGHT * reports.len() as f64 + 10.0
+}
+
+// labels the i-th row with its item, returning the row's vertical center
+fn row_label(svg: &mut String, i: usize, report: &ItemReport) -> f64 {
+    let y = ROW_TOP + ROW_HEIGHT * (i as f64 + 0.5);
+    let _ = write!(
+        svg,
+        r#"<text x="{:.1}" y="{:.1}" text-anchor="end" {FONT}>{}</text>"#,
+        ROW_LEFT - 8.0,
+        y + 4.0,
+        escape(&display_name(report.item))
+    );
+    y
+}
+
+// a vertical gridline across every row, labelled above the first
+fn gridline(svg: &mut String, x: f64, height: f64, label: &str) {
+    let _ = write!(
+        svg,
+        r##"<line x1="{x:.1}" y1="{:.1}" x2="{x:.1}" y2="{:.1}" stroke="#ddd"/><text x="{x:.1}" y="{:.1}" text-anchor="middle" {FONT}>{}</text>"##,
+        ROW_TOP - 6.0,
+        height - 10.0,
+        ROW_TOP - 10.0,
+        escape(label)
+    );
+}
+
+// bar segments centered on y, each given as its left edge, its share of the plot width, its
+// color and the weight it stands for
+fn segments(
+    svg: &mut String,
+    y: f64,
+    bars: impl IntoIterator<Item = (f64, f64, &'static str, f64)>,
+) {
+    for (x, share, color, weight) in bars {
+        let _ = write!(
+            svg,
+            r#"<rect x="{x:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{color}"><title>{}</title></rect>"#,
+            y - ROW_HEIGHT * 0.35,
+            share * plot_width(),
+            ROW_HEIGHT * 0.7,
+            number(weight)
+        );
+    }
+}
+
+fn open(width: f64, height: f64, title: &str) -> String {
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {width:.0} {height:.0}" width="{width:.0}" height="{height:.0}" role="img"><title>{}</title>"#,
+        escape(title)
+    )
+}
+
+// a row of color swatches, each followed by its name
+fn legend(svg: &mut String, x: f64, y: f64, entries: &[(&str, &str)]) {
+    let mut offset = 0.0;
+    for (name, color) in entries {
+        let _ = write!(
+            svg,
+            r#"<rect x="{:.1}" y="{:.1}" width="10" height="10" fill="{color}"/><text x="{:.1}" y="{:.1}" {FONT}>{}</text>"#,
+            x + offset,
+            y - 9.0,
+            x + offset + 14.0,
+            y,
+            escape(name)
+        );
+        // roughly the width of the name in the 11px font, plus some breathing room
+        offset += 14.0 + 6.5 * name.chars().count() as f64 + 14.0;
+    }
+}
+
+fn axis_line(svg: &mut String, x1: f64, y: f64, x2: f64) {
+    let _ = write!(
+        svg,
+        r##"<line x1="{x1:.1}" y1="{y:.1}" x2="{x2:.1}" y2="{y:.1}" stroke="#333"/>"##
+    );
+}
+
+// short enough for a tooltip or axis label, without trailing zeros on whole numbers
+fn number(value: f64) -> String {
+    let rounded = format!("{value:.2}");
+    rounded
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Fruit, report::report};
+
+    fn fruit(expected: Option<f64>, desired: Option<f64>) -> Fruit {
+        Fruit {
+            would_throw: true,
+            expected_rancidness: expected,
+            desired_rancidness: desired,
+            notes: String::new(),
+            expected_missing: None,
+            desired_missing: None,
+            imputation: None,
+            corrected: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn dumbbell_leaves_unanswered_items_off_the_axis() {
+        let reports = [
+            report("kiwi", &[fruit(None, None)], &[1.0]),
+            report("lime", &[fruit(Some(2.0), None)], &[1.0]),
+        ];
+        let svg = dumbbell(&reports);
+
+        assert!(svg.contains("no answers"));
+        assert_eq!(svg.matches("<circle").count(), 1);
+        assert!(svg.contains("<title>expected 2</title>"));
+        // only the gridlines, no line joining a lone point
+        assert_eq!(svg.matches("<line").count(), 5);
+    }
+}
@@ -0,0 +1,220 @@
+//! A human summary for the terminal: an aligned table of items with sparkline histograms,
+//! flagged items picked out in color, and what got rejected.
+
+use std::{collections::BTreeMap, fmt::Write as _};
+
+use anstyle::{AnsiColor, Style};
+
+use crate::{Flag, ItemReport, Reject, VecResponse, display_name, flags, missing::MissingRow};
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+const HEADERS: [&str; 9] = [
+    "item",
+    "throw",
+    "not",
+    "throw %",
+    "expected",
+    "desired",
+    "n",
+    "expected 1-5",
+    "desired 1-5",
+];
+// which of the columns above hold numbers, and so line up on the right
+const NUMERIC: [bool; 9] = [false, true, true, true, true, true, true, false, false];
+
+/// Renders the summary, with ansi colors if `color` is set.
+///
+/// `missing` is the [`missing::table`](crate::missing::table) of `vec_response`, and
+/// `reports` its [`VecResponse::report`].
+pub fn render(
+    vec_response: &VecResponse,
+    reports: &[ItemReport; 20],
+    missing: &[MissingRow],
+    rejects: &[Reject],
+    color: bool,
+) -> String {
+    let paint = |style: Style, text: &str| {
+        if color {
+            format!("{}{text}{}", style.render(), style.render_reset())
+        } else {
+            text.to_owned()
+        }
+    };
+
+    let rows = reports
+        .iter()
+        .map(|report| {
+            let cells = [
+                display_name(report.item),
+                number(report.weighted_would_throw_count),
+                number(report.weighted_would_not_throw_count),
+                format!("{:.0}%", report.throw_share() * 100.0),
+                format!("{:.2}", report.average_expected_rancidness),
+                format!("{:.2}", report.average_desired_rancidness),
+                format!("{:.0}", report.effective_sample_size),
+                sparkline(&report.expected_histogram),
+                sparkline(&report.desired_histogram),
+            ];
+            (cells, flags(report, missing))
+        })
+        .collect::<Vec<_>>();
+
+    let widths: [usize; 9] = std::array::from_fn(|i| {
+        rows.iter()
+            .map(|(cells, _)| cells[i].chars().count())
+            .chain([HEADERS[i].chars().count()])
+            .max()
+            .unwrap_or_default()
+    });
+    let line = |cells: [&str; 9]| {
+        cells
+            .iter()
+            .zip(widths)
+            .zip(NUMERIC)
+            .map(|((cell, width), numeric)| {
+                if numeric {
+                    format!("{cell:>width$}")
+                } else {
+                    format!("{cell:<width$}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+
+    let mut text = format!(
+        "{} responses, {} rejected rows\n\n",
+        vec_response.len(),
+        rejects.len()
+    );
+    let _ = writeln!(
+        text,
+        "{}",
+        paint(Style::new().bold(), line(HEADERS).trim_end())
+    );
+    for (cells, flags) in &rows {
+        let mut row = line(cells.each_ref().map(String::as_str));
+        for flag in flags {
+            row.push_str("  ");
+            row.push_str(&paint(flag_style(*flag), flag.describe()));
+        }
+        let _ = writeln!(text, "{}", row.trim_end());
+    }
+
+    if !rejects.is_empty() {
+        let mut by_reason = BTreeMap::<&str, Vec<u64>>::new();
+        for reject in rejects {
+            by_reason
+                .entry(reject.reason)
+                .or_default()
+                .push(reject.line);
+        }
+
+        let _ = writeln!(text, "\n{}", paint(Style::new().bold(), "rejected rows"));
+        for (reason, lines) in by_reason {
+            let _ = writeln!(
+                text,
+                "{:>4}  {reason} (line{} {})",
+                lines.len(),
+                if lines.len() == 1 { "" } else { "s" },
+                lines
+                    .iter()
+                    .map(u64::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+    }
+
+    text
+}
+
+fn flag_style(flag: Flag) -> Style {
+    let color = match flag {
+        Flag::LowSampleSize => AnsiColor::Yellow,
+        Flag::HighMissingness => AnsiColor::Red,
+        Flag::Contradiction => AnsiColor::Magenta,
+    };
+    Style::new().fg_color(Some(color.into()))
+}
+
+// one block per bin, as tall as its share of the fullest bin, with the lowest block kept for
+// empty bins so a handful of answers never reads as none
+fn sparkline(histogram: &[f64; 5]) -> String {
+    let max = histogram.iter().copied().fold(0.0, f64::max);
+    histogram
+        .iter()
+        .map(|bin| {
+            if *bin > 0.0 {
+                SPARKS[((bin / max * 7.0).round() as usize).max(1)]
+            } else {
+                SPARKS[0]
+            }
+        })
+        .collect()
+}
+
+// whole numbers for plain counts, a decimal place once weights make them fractional
+fn number(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.1}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Response, missing};
+
+    #[test]
+    fn sparklines_scale_to_the_fullest_bin() {
+        assert_eq!(sparkline(&[0.0, 1.0, 20.0, 10.0, 0.0]), "▁▂█▅▁");
+        assert_eq!(sparkline(&[0.0; 5]), "▁▁▁▁▁");
+        assert_eq!(sparkline(&[0.5, 0.5, 0.5, 0.5, 0.5]), "█████");
+    }
+
+    // responses answering the first item with `cells`, and every other item with a 3
+    fn vec_response(cells: &[[&str; 3]]) -> VecResponse {
+        cells
+            .iter()
+            .map(|cells| {
+                let row = cells
+                    .iter()
+                    .copied()
+                    .chain((1..20).flat_map(|_| ["Yes", "3", "3"]))
+                    .collect::<Vec<_>>();
+                Response::from_iter(&mut row.into_iter()).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn flags_small_and_patchy_items() {
+        let vec_response = vec_response(&[["Yes", "2", ""], ["No", "4", "5"], ["Yes", "1", "1"]]);
+        let reports = vec_response.report(&[1.0; 3]);
+        let text = render(
+            &vec_response,
+            &reports,
+            &missing::table(&vec_response),
+            &[],
+            false,
+        );
+        let lines = text.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines[0], "3 responses, 0 rejected rows");
+        assert!(lines[2].starts_with("item "));
+        let artichoke = lines[3];
+        assert!(artichoke.starts_with("Artichoke "));
+        assert!(artichoke.ends_with("low sample size  many missing answers"));
+        assert!(lines[4].ends_with("low sample size"));
+        // every row lines its columns up under the header
+        let sparkline_column = lines[2].find("expected 1-5").unwrap();
+        assert_eq!(
+            artichoke.chars().nth(sparkline_column),
+            sparkline(&reports[0].expected_histogram).chars().next()
+        );
+    }
+}
@@ -0,0 +1,214 @@
+//! Sorts the free-text notes into themes with a keyword lexicon, and tallies the themes per
+//! item.
+
+use std::{io, path::Path};
+
+use csv::Reader;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, VecResponse, normalize::normalize};
+
+/// The built-in lexicon, in the same `theme,pattern` csv layout a custom one is read from.
+///
+/// Its `refusal` theme is also what marks a rancidness answer as
+/// [`Missing::Refused`](crate::missing::Missing::Refused), see
+/// [`is_refusal`](crate::missing::is_refusal).
+pub const DEFAULT_LEXICON: &str = r#"theme,pattern
+refusal,\b(never|not|wouldn'?t|won'?t|can'?t|don'?t|refuse)\b.*\bthrow
+refusal,\bdo not\b
+refusal,\bplease\b
+refusal,\b(cruel|waste|wasteful|ethic\w*)\b
+size,\b(tiny|small|smol|little|big|huge|heavy|size)\b
+unfamiliar,\bnever heard\b
+unfamiliar,\bwhat (is|are) (this|that|these|it)\b
+unfamiliar,\b(idk|no idea|unfamiliar)\b
+unfamiliar,\bdon'?t know\b
+eat,\b(eat|eating|food|snack|love|try it)\b
+eat,\b(tast(e|es|y)|delicious|flavou?r|yum+)\b
+joke,\b(lol|lmao|haha\w*|funny|bro)\b
+joke,\b(witch|king|prince|minecraft|deepthroat\w*)\b
+joke,(asf|!!+)
+"#;
+
+/// Named themes, each matched by any of its case-insensitive regex patterns.
+#[derive(Debug, Clone)]
+pub struct Lexicon {
+    patterns: Vec<(String, Regex)>,
+}
+
+#[derive(Deserialize)]
+struct LexiconRow {
+    theme: String,
+    pattern: String,
+}
+
+impl Default for Lexicon {
+    fn default() -> Self {
+        Self::from_csv(DEFAULT_LEXICON.as_bytes()).expect("the built-in lexicon is valid")
+    }
+}
+
+impl Lexicon {
+    /// Reads a lexicon from a csv file with `theme` and `pattern` columns, one pattern per row.
+    pub fn from_path(path: &Path) -> Result<Self, Error> {
+        Self::from_csv(std::fs::File::open(path)?).map_err(Error::Lexicon)
+    }
+
+    /// Reads a lexicon from csv with `theme` and `pattern` columns, one pattern per row.
+    pub fn from_csv(reader: impl io::Read) -> Result<Self, String> {
+        let patterns = Reader::from_reader(reader)
+            .deserialize()
+            .map(|row| {
+                let row: LexiconRow = row.map_err(|e| format!("malformed lexicon: {e}"))?;
+                let regex = RegexBuilder::new(&row.pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| format!("bad lexicon pattern {:?}: {e}", row.pattern))?;
+                Ok((row.theme, regex))
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self { patterns })
+    }
+
+    /// Every theme, in the order they first appear in the lexicon.
+    pub fn themes(&self) -> Vec<&str> {
+        let mut themes = Vec::new();
+        for (theme, _) in &self.patterns {
+            if !themes.contains(&theme.as_str()) {
+                themes.push(theme.as_str());
+            }
+        }
+        themes
+    }
+
+    /// The themes `note` touches on, in lexicon order. The note is matched with its spelling
+    /// normalized, see [`normalize`].
+    pub fn classify(&self, note: &str) -> Vec<&str> {
+        let note = normalize(note);
+        let mut themes = Vec::new();
+        for (theme, regex) in &self.patterns {
+            if !themes.contains(&theme.as_str()) && regex.is_match(&note) {
+                themes.push(theme.as_str());
+            }
+        }
+        themes
+    }
+}
+
+/// One respondent's note on one item, with the themes it was sorted into.
+#[derive(Debug, Serialize)]
+pub struct NoteRow {
+    /// The respondent's position in the survey, counting from 0.
+    pub respondent: usize,
+    pub item: &'static str,
+    pub note: String,
+    /// The matching themes, separated by semicolons.
+    pub themes: String,
+}
+
+/// How many of the notes on one item touch on one theme.
+#[derive(Debug, Serialize)]
+pub struct ThemeRow {
+    pub item: &'static str,
+    pub theme: String,
+    pub count: usize,
+    /// The share of the item's notes touching on the theme.
+    pub share: f64,
+}
+
+/// Every non-empty note, item by item, with its themes.
+pub fn notes(vec_response: &VecResponse, lexicon: &Lexicon) -> Vec<NoteRow> {
+    vec_response
+        .items()
+        .into_iter()
+        .flat_map(|(item, fruits)| {
+            fruits
+                .iter()
+                .enumerate()
+                .filter(|(_, f)| !f.notes.trim().is_empty())
+                .map(move |(respondent, f)| NoteRow {
+                    respondent,
+                    item,
+                    note: f.notes.trim().to_owned(),
+                    themes: lexicon.classify(&f.notes).join(";"),
+                })
+        })
+        .collect()
+}
+
+/// Tallies every theme for every item, plus an `unclassified` row per item counting the
+/// notes no theme matched.
+pub fn table(vec_response: &VecResponse, lexicon: &Lexicon) -> Vec<ThemeRow> {
+    let themes = lexicon.themes();
+
+    vec_response
+        .items()
+        .into_iter()
+        .flat_map(|(item, fruits)| {
+            let classified = fruits
+                .iter()
+                .map(|f| f.notes.trim())
+                .filter(|n| !n.is_empty())
+                .map(|n| lexicon.classify(n))
+                .collect::<Vec<_>>();
+            let share = |count: usize| {
+                if classified.is_empty() {
+                    0.0
+                } else {
+                    count as f64 / classified.len() as f64
+                }
+            };
+
+            let mut rows = themes
+                .iter()
+                .map(|theme| {
+                    let count = classified.iter().filter(|t| t.contains(theme)).count();
+                    ThemeRow {
+                        item,
+                        theme: (*theme).to_owned(),
+                        count,
+                        share: share(count),
+                    }
+                })
+                .collect::<Vec<_>>();
+            let unclassified = classified.iter().filter(|t| t.is_empty()).count();
+            rows.push(ThemeRow {
+                item,
+                theme: "unclassified".to_owned(),
+                count: unclassified,
+                share: share(unclassified),
+            });
+            rows
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_notes_with_the_default_lexicon() {
+        let lexicon = Lexicon::default();
+
+        assert_eq!(lexicon.classify("I woukd never throw it"), ["refusal"]);
+        assert_eq!(
+            lexicon.classify("too tasty, I'd eat it lol"),
+            ["eat", "joke"]
+        );
+        assert_eq!(lexicon.classify("never heard of it"), ["unfamiliar"]);
+        assert_eq!(lexicon.classify("it's HUGE"), ["size"]);
+        assert!(lexicon.classify("sure").is_empty());
+    }
+
+    #[test]
+    fn themes_keep_lexicon_order() {
+        let lexicon = Lexicon::from_csv("theme,pattern\nb,x\na,y\nb,z\n".as_bytes()).unwrap();
+
+        assert_eq!(lexicon.themes(), ["b", "a"]);
+        assert_eq!(lexicon.classify("zyx"), ["b", "a"]);
+        assert!(Lexicon::from_csv("theme,pattern\na,(\n".as_bytes()).is_err());
+    }
+}
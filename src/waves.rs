@@ -0,0 +1,401 @@
+//! Several runs of the survey (waves) merged into one long data set, and how each item's
+//! answers moved from wave to wave.
+//!
+//! The lineup of items changes between events, so unlike [`Survey`](crate::Survey) a wave is
+//! read by header rather than by position: every "Would you throw a ...?" column starts an
+//! item, followed by its expected and desired rancidness columns.
+
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::LazyLock,
+};
+
+use regex::Regex;
+use serde::Serialize;
+use statrs::distribution::{ContinuousCDF, Normal, StudentsT};
+
+use crate::{
+    Error, Fruit, METADATA_COLUMNS, Reject, ResponseSource,
+    source::{self, Sheet},
+};
+
+/// A wave to read, given on the command line as `LABEL=PATH`.
+#[derive(Debug, Clone)]
+pub struct WaveSpec {
+    pub label: String,
+    pub path: PathBuf,
+}
+
+impl FromStr for WaveSpec {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((label, path)) if !label.is_empty() && !path.is_empty() => Ok(Self {
+                label: label.to_owned(),
+                path: PathBuf::from(path),
+            }),
+            _ => Err("expected a wave as LABEL=PATH"),
+        }
+    }
+}
+
+/// One respondent's massaged answers about one item in one wave, as a row of the combined
+/// data set.
+#[derive(Debug, Clone, Serialize)]
+pub struct Answer {
+    pub wave: String,
+    /// The respondent's position in the wave, counting from 0.
+    pub respondent: usize,
+    pub user_id: String,
+    pub item: String,
+    pub would_throw: bool,
+    pub expected_rancidness: Option<f64>,
+    pub desired_rancidness: Option<f64>,
+    pub notes: String,
+}
+
+/// One run of the survey.
+#[derive(Debug)]
+pub struct Wave {
+    pub label: String,
+    /// The items asked about, in survey order.
+    pub items: Vec<String>,
+    pub answers: Vec<Answer>,
+    /// The rows that couldn't be parsed, set aside as in a [`Survey`](crate::Survey).
+    pub rejects: Vec<Reject>,
+}
+
+/// The item a "Would you throw a ...?" header asks about, in the same snake_case as
+/// [`ITEMS`](crate::ITEMS), or `None` for any other header.
+pub fn item_name(header: &str) -> Option<String> {
+    static REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"(?i)^\s*would you throw an? (.+?)\s*\??\s*$").unwrap());
+
+    REGEX.captures(header).map(|captures| {
+        captures[1]
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join("_")
+            .to_lowercase()
+    })
+}
+
+impl Wave {
+    /// Reads a wave from a file, picking the format by extension.
+    pub fn from_path(label: &str, path: &Path) -> Result<Self, Error> {
+        Self::from_source(label, source::open(path, None, &Sheet::default())?.as_mut())
+    }
+
+    /// Reads a wave from any [`ResponseSource`], massaging every answer.
+    pub fn from_source(label: &str, source: &mut dyn ResponseSource) -> Result<Self, Error> {
+        let headers = source.headers()?;
+        let columns = headers
+            .iter()
+            .enumerate()
+            .skip(METADATA_COLUMNS)
+            .filter_map(|(i, header)| Some((item_name(header)?, i)))
+            .collect::<Vec<_>>();
+        if columns.is_empty() {
+            return Err(Error::Source("no \"would you throw\" columns in wave"));
+        }
+
+        let mut answers = Vec::new();
+        let mut rejects = Vec::new();
+        let mut respondent = 0;
+        while let Some(record) = source.next_row().transpose()? {
+            let fruits = columns
+                .iter()
+                .map(|(_, column)| Fruit::from_iter(&mut record.iter().skip(*column)))
+                .collect::<Result<Vec<_>, _>>();
+
+            match fruits {
+                Ok(fruits) => {
+                    let user_id = record.get(0).unwrap_or_default().trim();
+                    answers.extend(columns.iter().zip(fruits).map(|((item, _), fruit)| {
+                        let fruit = fruit.massage();
+                        Answer {
+                            wave: label.to_owned(),
+                            respondent,
+                            user_id: user_id.to_owned(),
+                            item: item.clone(),
+                            would_throw: fruit.would_throw,
+                            expected_rancidness: fruit.expected_rancidness,
+                            desired_rancidness: fruit.desired_rancidness,
+                            notes: fruit.notes,
+                        }
+                    }));
+                    respondent += 1;
+                }
+                Err(reason) => rejects.push(Reject {
+                    line: record.position().map_or(0, |p| p.line()),
+                    reason,
+                    record,
+                }),
+            }
+        }
+
+        Ok(Self {
+            label: label.to_owned(),
+            items: columns.into_iter().map(|(item, _)| item).collect(),
+            answers,
+            rejects,
+        })
+    }
+}
+
+/// One item's answers in one wave, and how they moved since the last wave that asked about
+/// the item.
+#[derive(Debug, Serialize)]
+pub struct Trend {
+    pub item: String,
+    pub wave: String,
+    pub respondents: usize,
+    pub throw_share: f64,
+    pub mean_expected_rancidness: f64,
+    pub mean_desired_rancidness: f64,
+    /// Empty in the first wave asking about the item, like the rest of the changes.
+    pub throw_share_change: Option<f64>,
+    /// Two-sided two-proportion z-test of the throw share change.
+    pub throw_share_p_value: Option<f64>,
+    pub mean_expected_rancidness_change: Option<f64>,
+    /// Two-sided Welch t-test of the mean expected rancidness change.
+    pub mean_expected_rancidness_p_value: Option<f64>,
+    pub mean_desired_rancidness_change: Option<f64>,
+    /// Two-sided Welch t-test of the mean desired rancidness change.
+    pub mean_desired_rancidness_p_value: Option<f64>,
+}
+
+/// Lines up every item across `waves` by name, in wave order, with items appearing in the
+/// order they were first asked about.
+pub fn trends(waves: &[Wave]) -> Vec<Trend> {
+    let mut items = Vec::<&str>::new();
+    for item in waves.iter().flat_map(|w| &w.items) {
+        if !items.contains(&item.as_str()) {
+            items.push(item);
+        }
+    }
+
+    items
+        .into_iter()
+        .flat_map(|item| {
+            let mut previous: Option<Vec<&Answer>> = None;
+            waves
+                .iter()
+                .filter(|w| w.items.iter().any(|i| i == item))
+                .map(|wave| {
+                    let answers = wave
+                        .answers
+                        .iter()
+                        .filter(|a| a.item == item)
+                        .collect::<Vec<_>>();
+                    let throws = answers.iter().map(|a| f64::from(u8::from(a.would_throw)));
+                    let expected = answers.iter().filter_map(|a| a.expected_rancidness);
+                    let desired = answers.iter().filter_map(|a| a.desired_rancidness);
+
+                    let trend = Trend {
+                        item: item.to_owned(),
+                        wave: wave.label.clone(),
+                        respondents: answers.len(),
+                        throw_share: mean(throws.clone()),
+                        mean_expected_rancidness: mean(expected.clone()),
+                        mean_desired_rancidness: mean(desired.clone()),
+                        throw_share_change: previous.as_ref().map(|before| {
+                            mean(throws.clone())
+                                - mean(before.iter().map(|a| f64::from(u8::from(a.would_throw))))
+                        }),
+                        throw_share_p_value: previous.as_ref().map(|before| {
+                            proportion_test(
+                                before.iter().filter(|a| a.would_throw).count(),
+                                before.len(),
+                                answers.iter().filter(|a| a.would_throw).count(),
+                                answers.len(),
+                            )
+                        }),
+                        mean_expected_rancidness_change: previous.as_ref().map(|before| {
+                            mean(expected.clone())
+                                - mean(before.iter().filter_map(|a| a.expected_rancidness))
+                        }),
+                        mean_expected_rancidness_p_value: previous.as_ref().map(|before| {
+                            welch_test(
+                                &before
+                                    .iter()
+                                    .filter_map(|a| a.expected_rancidness)
+                                    .collect::<Vec<_>>(),
+                                &expected.clone().collect::<Vec<_>>(),
+                            )
+                        }),
+                        mean_desired_rancidness_change: previous.as_ref().map(|before| {
+                            mean(desired.clone())
+                                - mean(before.iter().filter_map(|a| a.desired_rancidness))
+                        }),
+                        mean_desired_rancidness_p_value: previous.as_ref().map(|before| {
+                            welch_test(
+                                &before
+                                    .iter()
+                                    .filter_map(|a| a.desired_rancidness)
+                                    .collect::<Vec<_>>(),
+                                &desired.clone().collect::<Vec<_>>(),
+                            )
+                        }),
+                    };
+                    previous = Some(answers);
+                    trend
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0), |(s, c), v| (s + v, c + 1));
+    if count > 0 {
+        sum / count as f64
+    } else {
+        f64::NAN
+    }
+}
+
+// two-sided p-value for a change between x1 of n1 and x2 of n2, pooling the two shares
+fn proportion_test(x1: usize, n1: usize, x2: usize, n2: usize) -> f64 {
+    if n1 == 0 || n2 == 0 {
+        return f64::NAN;
+    }
+    let (p1, p2) = (x1 as f64 / n1 as f64, x2 as f64 / n2 as f64);
+    let pooled = (x1 + x2) as f64 / (n1 + n2) as f64;
+    let standard_error = (pooled * (1.0 - pooled) * (1.0 / n1 as f64 + 1.0 / n2 as f64)).sqrt();
+
+    if standard_error == 0.0 {
+        // everyone answered the same in both waves
+        return 1.0;
+    }
+    let z = (p2 - p1) / standard_error;
+    2.0 * Normal::standard().sf(z.abs())
+}
+
+// two-sided p-value for a change in mean between two samples with unequal variances
+fn welch_test(before: &[f64], after: &[f64]) -> f64 {
+    let variance = |values: &[f64]| {
+        let mean = mean(values.iter().copied());
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() as f64 - 1.0)
+    };
+    if before.len() < 2 || after.len() < 2 {
+        return f64::NAN;
+    }
+
+    let (n1, n2) = (before.len() as f64, after.len() as f64);
+    let (v1, v2) = (variance(before) / n1, variance(after) / n2);
+    let difference = mean(after.iter().copied()) - mean(before.iter().copied());
+    if v1 + v2 == 0.0 {
+        return if difference == 0.0 { 1.0 } else { 0.0 };
+    }
+
+    let t = difference / (v1 + v2).sqrt();
+    let degrees_of_freedom = (v1 + v2).powi(2) / (v1 * v1 / (n1 - 1.0) + v2 * v2 / (n2 - 1.0));
+    match StudentsT::new(0.0, 1.0, degrees_of_freedom) {
+        Ok(distribution) => 2.0 * distribution.sf(t.abs()),
+        Err(_) => f64::NAN,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_item_names_from_headers() {
+        assert_eq!(
+            item_name("Would you throw a Brussels Sprout?").as_deref(),
+            Some("brussels_sprout")
+        );
+        assert_eq!(
+            item_name("would you throw an artichoke").as_deref(),
+            Some("artichoke")
+        );
+        assert_eq!(item_name("How rancid do you expect it to be?"), None);
+    }
+
+    #[test]
+    fn proportion_test_matches_textbook() {
+        // 30 of 50 against 20 of 50: z = 2.0, p = 0.0455
+        assert!((proportion_test(30, 50, 20, 50) - 0.0455).abs() < 1e-4);
+        assert_eq!(proportion_test(5, 5, 5, 5), 1.0);
+        assert!(proportion_test(0, 0, 1, 2).is_nan());
+    }
+
+    #[test]
+    fn welch_test_matches_textbook() {
+        let before = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let after = [3.0, 4.0, 5.0, 6.0, 7.0];
+        // t = 2.0 on 8 degrees of freedom
+        assert!((welch_test(&before, &after) - 0.0805).abs() < 1e-4);
+        assert_eq!(welch_test(&[2.0, 2.0], &[2.0, 2.0]), 1.0);
+        assert_eq!(welch_test(&[2.0, 2.0], &[3.0, 3.0]), 0.0);
+        assert!(welch_test(&[1.0], &after).is_nan());
+    }
+
+    fn answer(wave: &str, item: &str, would_throw: bool, rancidness: f64) -> Answer {
+        Answer {
+            wave: wave.to_owned(),
+            respondent: 0,
+            user_id: String::new(),
+            item: item.to_owned(),
+            would_throw,
+            expected_rancidness: Some(rancidness),
+            desired_rancidness: Some(rancidness + 1.0),
+            notes: String::new(),
+        }
+    }
+
+    fn wave(label: &str, answers: Vec<Answer>) -> Wave {
+        let mut items = Vec::<String>::new();
+        for answer in &answers {
+            if !items.contains(&answer.item) {
+                items.push(answer.item.clone());
+            }
+        }
+        Wave {
+            label: label.to_owned(),
+            items,
+            answers,
+            rejects: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn trends_line_items_up_by_name() {
+        let waves = [
+            wave(
+                "spring",
+                vec![
+                    answer("spring", "kiwi", true, 2.0),
+                    answer("spring", "kiwi", false, 4.0),
+                    answer("spring", "lime", true, 1.0),
+                ],
+            ),
+            wave(
+                "fall",
+                vec![
+                    answer("fall", "kiwi", true, 4.0),
+                    answer("fall", "kiwi", true, 4.0),
+                ],
+            ),
+        ];
+        let trends = trends(&waves);
+
+        let rows = trends
+            .iter()
+            .map(|t| (t.item.as_str(), t.wave.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            [("kiwi", "spring"), ("kiwi", "fall"), ("lime", "spring")]
+        );
+        assert_eq!(trends[0].throw_share_change, None);
+        assert_eq!(trends[1].throw_share_change, Some(0.5));
+        assert_eq!(trends[1].mean_expected_rancidness_change, Some(1.0));
+        assert_eq!(trends[1].mean_desired_rancidness_change, Some(1.0));
+        assert!(trends[1].mean_expected_rancidness_p_value.is_some());
+    }
+}
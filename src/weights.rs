@@ -0,0 +1,134 @@
+//! Per-respondent weights, honored by every statistic in [`report`](crate::report).
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use csv::{Reader, StringRecord};
+use serde::Deserialize;
+
+use crate::Response;
+
+/// Where respondent weights come from.
+#[derive(Debug, Clone, Default)]
+pub enum Weighting {
+    /// Every respondent counts once.
+    #[default]
+    Uniform,
+    /// A numeric column of the survey export, named by its header.
+    Column(String),
+    /// A csv with `User ID` and `weight` columns.
+    File(PathBuf),
+    /// The share of the respondent's answers that didn't need coercing.
+    Quality,
+}
+
+#[derive(Deserialize)]
+struct WeightRow {
+    #[serde(rename = "User ID")]
+    user_id: String,
+    weight: f64,
+}
+
+/// Reads each respondent's weight from the named column.
+pub fn from_column(
+    headers: &StringRecord,
+    records: &[StringRecord],
+    column: &str,
+) -> Result<Vec<f64>, String> {
+    let index = headers
+        .iter()
+        .position(|h| h == column)
+        .ok_or_else(|| format!("weight column {column:?} not found"))?;
+
+    records
+        .iter()
+        .map(|r| {
+            r.get(index)
+                .ok_or("end of row")?
+                .trim()
+                .parse()
+                .map_err(|_| format!("malformed weight in row {:?}", r.position()))
+        })
+        .collect()
+}
+
+/// Looks up each respondent's weight by User ID. Respondents without a User ID can't be
+/// looked up and keep a weight of 1, but a User ID missing from the file is an error, since
+/// a stale or mistyped file would otherwise quietly weigh those respondents at 1.
+pub fn from_file(path: &Path, records: &[StringRecord]) -> Result<Vec<f64>, String> {
+    let weights = Reader::from_path(path)
+        .map_err(|e| format!("couldn't open weight file: {e}"))?
+        .deserialize()
+        .map(|r| r.map(|r: WeightRow| (r.user_id, r.weight)))
+        .collect::<Result<HashMap<_, _>, _>>()
+        .map_err(|e| format!("malformed weight file: {e}"))?;
+
+    records
+        .iter()
+        .map(|r| match r.get(0) {
+            Some(id) if !id.is_empty() => weights
+                .get(id)
+                .copied()
+                .ok_or_else(|| format!("no weight for User ID {id:?} in weight file")),
+            _ => Ok(1.0),
+        })
+        .collect()
+}
+
+/// Checks weights are usable: finite, not negative, and not all zero.
+pub fn validate(weights: Vec<f64>) -> Result<Vec<f64>, String> {
+    if let Some(weight) = weights.iter().find(|w| !w.is_finite() || **w < 0.0) {
+        return Err(format!(
+            "weights must be finite and not negative, got {weight}"
+        ));
+    }
+    if !weights.is_empty() && weights.iter().all(|w| *w == 0.0) {
+        return Err("every weight is zero".to_owned());
+    }
+    Ok(weights)
+}
+
+/// Weights each respondent by the share of their items answered without coercion.
+pub fn from_quality(responses: &[Response]) -> Vec<f64> {
+    responses
+        .iter()
+        .map(|r| {
+            let fruits = r.fruits();
+            let coerced = fruits.iter().filter(|f| f.is_coerced()).count();
+
+            (fruits.len() - coerced) as f64 / fruits.len() as f64
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_rejects_unusable_weights() {
+        assert!(validate(vec![1.0, f64::NAN]).is_err());
+        assert!(validate(vec![1.0, f64::INFINITY]).is_err());
+        assert!(validate(vec![1.0, -0.5]).is_err());
+        assert!(validate(vec![0.0, 0.0]).is_err());
+        assert_eq!(validate(vec![0.0, 2.0]), Ok(vec![0.0, 2.0]));
+        assert_eq!(validate(Vec::new()), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn from_column_reads_named_column() {
+        let headers = StringRecord::from(vec!["User ID", "weight"]);
+        let records = [
+            StringRecord::from(vec!["a", " 2.5 "]),
+            StringRecord::from(vec!["b", "1"]),
+        ];
+
+        assert_eq!(
+            from_column(&headers, &records, "weight"),
+            Ok(vec![2.5, 1.0])
+        );
+        assert!(from_column(&headers, &records, "nope").is_err());
+    }
+}
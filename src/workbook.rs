@@ -0,0 +1,286 @@
+//! Writes report artifacts out as a single ods or xlsx workbook, one sheet per artifact.
+
+use std::{fmt::Write as _, fs::File, io::Write, path::Path};
+
+use rust_xlsxwriter::{Format, Workbook};
+use serde::Serialize;
+use serde_json::Value;
+use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+use crate::{Error, Survey, escape};
+
+/// A typed spreadsheet cell.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Empty,
+    Number(f64),
+    Bool(bool),
+    Text(String),
+}
+
+impl Cell {
+    fn width(&self) -> usize {
+        match self {
+            Self::Empty => 0,
+            Self::Number(n) => n.to_string().len(),
+            Self::Bool(_) => 5,
+            Self::Text(t) => t.chars().count(),
+        }
+    }
+}
+
+impl From<Value> for Cell {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => Self::Empty,
+            Value::Bool(b) => Self::Bool(b),
+            Value::Number(n) => n.as_f64().map_or(Self::Empty, Self::Number),
+            Value::String(s) => Self::Text(s),
+            other => Self::Text(other.to_string()),
+        }
+    }
+}
+
+/// One sheet's worth of data: a header row followed by typed rows.
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub name: String,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    /// Lays out serializable rows (like [`FlattenedResponse`](crate::FlattenedResponse) or
+    /// [`ItemReport`](crate::ItemReport)) with one column per field, keeping numbers and
+    /// bools typed.
+    pub fn from_rows<T: Serialize>(
+        name: impl Into<String>,
+        rows: impl IntoIterator<Item = T>,
+    ) -> Result<Self, Error> {
+        let mut headers = Vec::new();
+        let rows = rows
+            .into_iter()
+            .map(|row| match serde_json::to_value(row)? {
+                Value::Object(object) => {
+                    if headers.is_empty() {
+                        headers = object.keys().cloned().collect();
+                    }
+                    Ok(object.into_iter().map(|(_, v)| Cell::from(v)).collect())
+                }
+                other => Ok(vec![Cell::from(other)]),
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Self {
+            name: name.into(),
+            headers,
+            rows,
+        })
+    }
+
+    /// The survey's rejected rows, with why they were rejected alongside the raw cells.
+    pub fn rejects(survey: &Survey) -> Self {
+        Self {
+            name: "rejects".to_owned(),
+            headers: ["line", "reason"]
+                .into_iter()
+                .chain(&survey.headers)
+                .map(str::to_owned)
+                .collect(),
+            rows: survey
+                .rejects
+                .iter()
+                .map(|r| {
+                    [Cell::Number(r.line as f64), Cell::Text(r.reason.to_owned())]
+                        .into_iter()
+                        .chain(r.record.iter().map(|c| match c {
+                            "" => Cell::Empty,
+                            c => Cell::Text(c.to_owned()),
+                        }))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    // character widths, wide enough for the header and every cell but capped so prose
+    // doesn't push everything else off screen
+    fn column_widths(&self) -> Vec<usize> {
+        self.headers
+            .iter()
+            .enumerate()
+            .map(|(i, header)| {
+                self.rows
+                    .iter()
+                    .filter_map(|r| r.get(i))
+                    .map(Cell::width)
+                    .chain([header.chars().count()])
+                    .max()
+                    .unwrap_or_default()
+                    .clamp(8, 60)
+                    + 2
+            })
+            .collect()
+    }
+}
+
+/// Writes every table to its own sheet of a workbook, as ods if `path` ends in `.ods` and
+/// xlsx otherwise. Header rows are bold and frozen, and columns are sized to fit.
+pub fn write(path: &Path, tables: &[Table]) -> Result<(), Error> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(e) if e.eq_ignore_ascii_case("ods") => write_ods(path, tables),
+        _ => write_xlsx(path, tables),
+    }
+}
+
+fn write_xlsx(path: &Path, tables: &[Table]) -> Result<(), Error> {
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+
+    for table in tables {
+        let sheet = workbook.add_worksheet().set_name(&table.name)?;
+
+        for (column, header) in (0..).zip(&table.headers) {
+            sheet.write_string_with_format(0, column, header, &bold)?;
+        }
+        for (row, cells) in (1..).zip(&table.rows) {
+            for (column, cell) in (0..).zip(cells) {
+                match cell {
+                    Cell::Empty => continue,
+                    Cell::Number(n) => sheet.write_number(row, column, *n)?,
+                    Cell::Bool(b) => sheet.write_boolean(row, column, *b)?,
+                    Cell::Text(t) => sheet.write_string(row, column, t)?,
+                };
+            }
+        }
+        for (column, width) in (0..).zip(table.column_widths()) {
+            sheet.set_column_width(column, width as f64)?;
+        }
+        sheet.set_freeze_panes(1, 0)?;
+    }
+
+    workbook.save(path)?;
+    Ok(())
+}
+
+const OFFICE: &str = "urn:oasis:names:tc:opendocument:xmlns:office:1.0";
+
+fn write_ods(path: &Path, tables: &[Table]) -> Result<(), Error> {
+    let mut zip = ZipWriter::new(File::create(path)?);
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    // the mimetype has to come first, uncompressed, for the file to be recognized
+    zip.start_file(
+        "mimetype",
+        SimpleFileOptions::default().compression_method(CompressionMethod::Stored),
+    )?;
+    zip.write_all(b"application/vnd.oasis.opendocument.spreadsheet")?;
+
+    zip.start_file("META-INF/manifest.xml", deflated)?;
+    zip.write_all(MANIFEST.as_bytes())?;
+
+    zip.start_file("settings.xml", deflated)?;
+    zip.write_all(ods_settings(tables).as_bytes())?;
+
+    zip.start_file("content.xml", deflated)?;
+    zip.write_all(ods_content(tables).as_bytes())?;
+
+    zip.finish()?;
+    Ok(())
+}
+
+const MANIFEST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.2">
+ <manifest:file-entry manifest:full-path="/" manifest:version="1.2" manifest:media-type="application/vnd.oasis.opendocument.spreadsheet"/>
+ <manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/>
+ <manifest:file-entry manifest:full-path="settings.xml" manifest:media-type="text/xml"/>
+</manifest:manifest>
+"#;
+
+// ods has no notion of frozen panes in the document itself, it's a view setting
+fn ods_settings(tables: &[Table]) -> String {
+    let mut xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><office:document-settings xmlns:office="{OFFICE}" xmlns:config="urn:oasis:names:tc:opendocument:xmlns:config:1.0" office:version="1.2"><office:settings><config:config-item-set config:name="ooo:view-settings"><config:config-item-map-indexed config:name="Views"><config:config-item-map-entry><config:config-item config:name="ViewId" config:type="string">view1</config:config-item><config:config-item-map-named config:name="Tables">"#
+    );
+    for table in tables {
+        let _ = write!(
+            xml,
+            r#"<config:config-item-map-entry config:name="{}"><config:config-item config:name="VerticalSplitMode" config:type="short">2</config:config-item><config:config-item config:name="VerticalSplitPosition" config:type="int">1</config:config-item><config:config-item config:name="ActiveSplitRange" config:type="short">2</config:config-item><config:config-item config:name="PositionBottom" config:type="int">1</config:config-item></config:config-item-map-entry>"#,
+            escape(&table.name)
+        );
+    }
+    xml.push_str("</config:config-item-map-named></config:config-item-map-entry></config:config-item-map-indexed></config:config-item-set></office:settings></office:document-settings>");
+    xml
+}
+
+fn ods_content(tables: &[Table]) -> String {
+    let widths = tables.iter().map(Table::column_widths).collect::<Vec<_>>();
+    let mut distinct = widths.iter().flatten().copied().collect::<Vec<_>>();
+    distinct.sort_unstable();
+    distinct.dedup();
+
+    let mut xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><office:document-content xmlns:office="{OFFICE}" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0" office:version="1.2"><office:automatic-styles>"#
+    );
+    for width in &distinct {
+        // roughly what a character of the default font takes up
+        let _ = write!(
+            xml,
+            r#"<style:style style:name="co{width}" style:family="table-column"><style:table-column-properties style:column-width="{:.3}in"/></style:style>"#,
+            *width as f64 * 0.075
+        );
+    }
+    xml.push_str(r#"<style:style style:name="header" style:family="table-cell"><style:text-properties fo:font-weight="bold"/></style:style></office:automatic-styles><office:body><office:spreadsheet>"#);
+
+    for (table, widths) in tables.iter().zip(&widths) {
+        let _ = write!(xml, r#"<table:table table:name="{}">"#, escape(&table.name));
+        for width in widths {
+            let _ = write!(xml, r#"<table:table-column table:style-name="co{width}"/>"#);
+        }
+
+        xml.push_str("<table:table-header-rows><table:table-row>");
+        for header in &table.headers {
+            let _ = write!(
+                xml,
+                r#"<table:table-cell table:style-name="header" office:value-type="string"><text:p>{}</text:p></table:table-cell>"#,
+                escape(header)
+            );
+        }
+        xml.push_str("</table:table-row></table:table-header-rows>");
+
+        for row in &table.rows {
+            xml.push_str("<table:table-row>");
+            for cell in row {
+                match cell {
+                    Cell::Empty => xml.push_str("<table:table-cell/>"),
+                    Cell::Number(n) => {
+                        let _ = write!(
+                            xml,
+                            r#"<table:table-cell office:value-type="float" office:value="{n}"><text:p>{n}</text:p></table:table-cell>"#
+                        );
+                    }
+                    Cell::Bool(b) => {
+                        let _ = write!(
+                            xml,
+                            r#"<table:table-cell office:value-type="boolean" office:boolean-value="{b}"><text:p>{}</text:p></table:table-cell>"#,
+                            if *b { "TRUE" } else { "FALSE" }
+                        );
+                    }
+                    Cell::Text(t) => {
+                        let _ = write!(
+                            xml,
+                            r#"<table:table-cell office:value-type="string"><text:p>{}</text:p></table:table-cell>"#,
+                            escape(t)
+                        );
+                    }
+                }
+            }
+            xml.push_str("</table:table-row>");
+        }
+
+        xml.push_str("</table:table>");
+    }
+
+    xml.push_str("</office:spreadsheet></office:body></office:document-content>");
+    xml
+}
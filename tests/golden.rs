@@ -0,0 +1,60 @@
+//! Renders a small survey through the svg, html and markdown renderers and compares the output
+//! with the files in `tests/golden`. Run with `UPDATE_GOLDEN=1` to rewrite them after an
+//! intended change to a renderer, and check the diff.
+
+use std::{env, fs, path::Path};
+
+use throw_cruncher::{Question, Survey, html, keywords, markdown, missing, svg};
+
+fn check(name: &str, rendered: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name);
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, rendered).unwrap();
+        return;
+    }
+
+    let golden = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("couldn't read {}: {e}", path.display()));
+    assert!(
+        golden == rendered,
+        "{name} no longer matches its golden file, rerun with UPDATE_GOLDEN=1 if that's intended"
+    );
+}
+
+#[test]
+fn renderers_match_golden_files() {
+    let mut survey =
+        Survey::from_path(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/survey.csv"))
+            .unwrap()
+            .massage();
+    let rejects = std::mem::take(&mut survey.rejects);
+    let vec_response = survey.into_vec_response();
+    let weights = vec![1.0; vec_response.len()];
+    let reports = vec_response.report(&weights);
+    let missing = missing::table(&vec_response);
+
+    check("report.md", &markdown::render(&vec_response, &reports));
+    check(
+        "report.html",
+        &html::render(&vec_response, &reports, &missing, &rejects),
+    );
+    check("throw.svg", &svg::throw_bars(&reports));
+    check("artichoke.svg", &svg::histogram(&reports[0]));
+    check("dumbbell.svg", &svg::dumbbell(&reports));
+    check(
+        "likert_expected.svg",
+        &svg::likert(&reports, Question::Expected),
+    );
+    check(
+        "likert_desired.svg",
+        &svg::likert(&reports, Question::Desired),
+    );
+
+    let overall = keywords::table(&vec_response, 10)
+        .into_iter()
+        .filter(|t| t.item == "overall")
+        .collect::<Vec<_>>();
+    check("words.svg", &svg::word_cloud(&overall, "Most common words"));
+}